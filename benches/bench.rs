@@ -29,3 +29,61 @@ fn scanlines(b: &mut Bencher) {
         buf
     });
 }
+
+#[bench]
+fn scaled_load(b: &mut Bencher) {
+    let f = &include_bytes!("../assets/colorful_studio_2k.hdr")[..];
+    b.iter(|| radiant::Loader::new(f).unwrap().load_scaled(4).unwrap());
+}
+
+#[bench]
+fn slice_load(b: &mut Bencher) {
+    let f = &include_bytes!("../assets/colorful_studio_2k.hdr")[..];
+    b.iter(|| radiant::load_from_slice(f).unwrap());
+}
+
+/// A 2048x1024 image with the new run-length encoding, with literal spans in the colour channels
+/// and runs in the exponent channel.
+fn synthetic_image() -> Vec<u8> {
+    let (width, height) = (2048_usize, 1024_usize);
+    let mut file = format!("#?RADIANCE\n\n-Y {} +X {}\n", height, width).into_bytes();
+    for y in 0..height {
+        file.extend_from_slice(&[2, 2, (width >> 8) as u8, width as u8]);
+        for channel in 0..3 {
+            for chunk in (0..width).collect::<Vec<_>>().chunks(128) {
+                file.push(chunk.len() as u8);
+                file.extend(chunk.iter().map(|&x| (x * (channel + 1) + y) as u8));
+            }
+        }
+        let mut left = width;
+        while left > 0 {
+            let count = left.min(127);
+            file.extend_from_slice(&[128 | count as u8, 128 + (y % 8) as u8]);
+            left -= count;
+        }
+    }
+    file
+}
+
+#[bench]
+fn synthetic_load(b: &mut Bencher) {
+    let f = synthetic_image();
+    b.iter(|| radiant::load(&f[..]).unwrap());
+}
+
+#[bench]
+fn synthetic_slice_load(b: &mut Bencher) {
+    let f = synthetic_image();
+    b.iter(|| radiant::load_from_slice(&f).unwrap());
+}
+
+#[bench]
+fn synthetic_region_load(b: &mut Bencher) {
+    let f = synthetic_image();
+    b.iter(|| {
+        radiant::Loader::new(&f[..])
+            .unwrap()
+            .load_region(896, 0, 256, 1024)
+            .unwrap()
+    });
+}
@@ -0,0 +1,160 @@
+use crate::{Image, Rgb};
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Implement an arithmetic operator componentwise between two pixels, and between a pixel and a
+/// scalar that applies to every channel.
+macro_rules! impl_op {
+    ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident) => {
+        impl $Op for Rgb {
+            type Output = Rgb;
+
+            #[inline]
+            fn $op(self, other: Rgb) -> Rgb {
+                Rgb {
+                    r: self.r.$op(other.r),
+                    g: self.g.$op(other.g),
+                    b: self.b.$op(other.b),
+                }
+            }
+        }
+
+        impl $Op<f32> for Rgb {
+            type Output = Rgb;
+
+            #[inline]
+            fn $op(self, scalar: f32) -> Rgb {
+                Rgb {
+                    r: self.r.$op(scalar),
+                    g: self.g.$op(scalar),
+                    b: self.b.$op(scalar),
+                }
+            }
+        }
+
+        impl $OpAssign for Rgb {
+            #[inline]
+            fn $op_assign(&mut self, other: Rgb) {
+                *self = (*self).$op(other);
+            }
+        }
+
+        impl $OpAssign<f32> for Rgb {
+            #[inline]
+            fn $op_assign(&mut self, scalar: f32) {
+                *self = (*self).$op(scalar);
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, AddAssign, add_assign);
+impl_op!(Sub, sub, SubAssign, sub_assign);
+impl_op!(Mul, mul, MulAssign, mul_assign);
+impl_op!(Div, div, DivAssign, div_assign);
+
+impl Mul<Rgb> for f32 {
+    type Output = Rgb;
+
+    #[inline]
+    fn mul(self, rgb: Rgb) -> Rgb {
+        rgb * self
+    }
+}
+
+impl Neg for Rgb {
+    type Output = Rgb;
+
+    #[inline]
+    fn neg(self) -> Rgb {
+        Rgb {
+            r: -self.r,
+            g: -self.g,
+            b: -self.b,
+        }
+    }
+}
+
+impl Sum for Rgb {
+    fn sum<I: Iterator<Item = Rgb>>(iter: I) -> Rgb {
+        iter.fold(Rgb::zero(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Rgb> for Rgb {
+    fn sum<I: Iterator<Item = &'a Rgb>>(iter: I) -> Rgb {
+        iter.copied().sum()
+    }
+}
+
+/// Per-pixel arithmetic, like a small subset of Radiance's `pcomb`. Operations between two images
+/// panic if their dimensions differ.
+impl Image {
+    /// Apply a function to every pixel in place.
+    pub fn map(&mut self, mut f: impl FnMut(Rgb) -> Rgb) {
+        for rgb in &mut self.data {
+            *rgb = f(*rgb);
+        }
+    }
+
+    /// Combine every pixel with the pixel at the same position in another image, in place.
+    pub fn zip_map(&mut self, other: &Image, mut f: impl FnMut(Rgb, Rgb) -> Rgb) {
+        assert!(
+            self.width == other.width
+                && self.height == other.height
+                && self.data.len() == other.data.len(),
+            "the images must have the same dimensions"
+        );
+
+        for (rgb, &other) in self.data.iter_mut().zip(&other.data) {
+            *rgb = f(*rgb, other);
+        }
+    }
+
+    /// Multiply every channel by a factor, e.g. to change the exposure.
+    pub fn scale(&mut self, factor: f32) {
+        self.map(|rgb| rgb * factor);
+    }
+
+    /// Add another image to this one.
+    pub fn add(&mut self, other: &Image) {
+        self.zip_map(other, |a, b| a + b);
+    }
+
+    /// Multiply this image by another one, channel by channel.
+    pub fn multiply(&mut self, other: &Image) {
+        self.zip_map(other, |a, b| a * b);
+    }
+
+    /// Blend towards another image, where `t` is 0 for this image and 1 for the other one.
+    pub fn lerp(&mut self, other: &Image, t: f32) {
+        self.zip_map(other, |a, b| a.lerp(b, t));
+    }
+
+    /// Clamp every channel to be between `min` and `max`. NaN channels are left alone.
+    ///
+    /// Panics if `min` is greater than `max`, or if either of them is NaN.
+    pub fn clamp(&mut self, min: f32, max: f32) {
+        assert!(
+            min <= max,
+            "the minimum must not be greater than the maximum"
+        );
+
+        self.map(|rgb| Rgb {
+            r: rgb.r.clamp(min, max),
+            g: rgb.g.clamp(min, max),
+            b: rgb.b.clamp(min, max),
+        });
+    }
+
+    /// Replace every channel that is NaN or infinite, which can otherwise spread through filters
+    /// and averages.
+    pub fn replace_non_finite(&mut self, replacement: f32) {
+        let replace = |c: f32| if c.is_finite() { c } else { replacement };
+        self.map(|rgb| Rgb {
+            r: replace(rgb.r),
+            g: replace(rgb.g),
+            b: replace(rgb.b),
+        });
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::{bail, Context, Result};
+use radiant::tonemap::{Curve, ToneMap};
+use radiant::{pfm, Encoder, Fill, Header, Image, Loader};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// Inspect, validate and convert Radiance HDR images.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "radiant")]
+enum Command {
+    /// Print the dimensions, header and luminance statistics of an image.
+    #[structopt(name = "info")]
+    Info { image_path: PathBuf },
+    /// Decode every scanline of an image, and report the rows that are corrupt or missing.
+    #[structopt(name = "validate")]
+    Validate { image_path: PathBuf },
+    /// Convert between .hdr, .pfm, and tone-mapped .ppm and .pgm files, by file extension.
+    #[structopt(
+        name = "convert",
+        raw(setting = "structopt::clap::AppSettings::AllowNegativeNumbers")
+    )]
+    Convert {
+        input_path: PathBuf,
+        output_path: PathBuf,
+        /// Write .hdr scanlines as flat pixels instead of run-length encoding them.
+        #[structopt(long = "flat")]
+        flat: bool,
+        /// Write a 16-bit .ppm instead of an 8-bit one.
+        #[structopt(long = "16-bit")]
+        sixteen_bit: bool,
+        /// The exposure adjustment for .ppm and .pgm output, in stops.
+        #[structopt(long = "exposure", default_value = "0")]
+        exposure: f32,
+        /// The tone curve for .ppm and .pgm output: linear, srgb, reinhard, aces or gamma=<value>.
+        #[structopt(
+            long = "curve",
+            default_value = "srgb",
+            parse(try_from_str = "parse_curve")
+        )]
+        curve: Curve,
+    },
+}
+
+fn parse_curve(curve: &str) -> std::result::Result<Curve, String> {
+    match curve {
+        "linear" => Ok(Curve::Linear),
+        "srgb" => Ok(Curve::Srgb),
+        "reinhard" => Ok(Curve::Reinhard),
+        "aces" => Ok(Curve::Aces),
+        _ => curve
+            .strip_prefix("gamma=")
+            .and_then(|gamma| gamma.parse().ok())
+            .map(Curve::Gamma)
+            .ok_or_else(|| format!("unknown tone curve: {}", curve)),
+    }
+}
+
+fn open(path: &Path) -> Result<Loader<BufReader<File>>> {
+    let f = File::open(path).context("failed to open specified file")?;
+    Loader::new(BufReader::new(f)).context("failed to read image header")
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+fn info(path: &Path) -> Result<()> {
+    let loader = open(path)?;
+
+    println!("dimensions:  {} x {}", loader.width, loader.height);
+    println!("orientation: -Y +X (rows from top to bottom, columns from left to right)");
+    println!(
+        "format:      {}",
+        loader.header.format().unwrap_or("unspecified")
+    );
+
+    if !loader.header.variables.is_empty() {
+        println!("variables:");
+        for (name, value) in &loader.header.variables {
+            println!("  {}={}", name, value);
+        }
+    }
+    if !loader.header.other.is_empty() {
+        println!("other header lines:");
+        for line in &loader.header.other {
+            println!("  {}", line);
+        }
+    }
+
+    // Undo the exposure that has been applied to the pixels since they were rendered
+    let exposure = loader.header.exposure();
+    let image = loader.load_image().context("failed to load image data")?;
+    let luminances: Vec<f32> = image.luminance().iter().map(|l| l / exposure).collect();
+
+    if !luminances.is_empty() {
+        let count = luminances.len() as f64;
+        let min = luminances.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = luminances.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let mean = luminances.iter().map(|&l| l as f64).sum::<f64>() / count;
+        // The geometric mean, offset slightly so that black pixels don't dominate it
+        let log_mean = luminances
+            .iter()
+            .map(|&l| (1e-2 + l.max(0.) as f64).ln())
+            .sum::<f64>()
+            / count;
+
+        if exposure == 1. {
+            println!("luminance (cd/m²):");
+        } else {
+            println!("luminance (cd/m², divided by EXPOSURE={}):", exposure);
+        }
+        println!("  min:         {}", min);
+        println!("  max:         {}", max);
+        println!("  mean:        {}", mean);
+        println!("  log average: {}", log_mean.exp());
+    }
+
+    Ok(())
+}
+
+fn validate(path: &Path) -> Result<()> {
+    let loader = open(path)?;
+    let (width, height) = (loader.width, loader.height);
+    let recovered = loader
+        .load_image_lenient(Fill::Black)
+        .context("failed to load image data")?;
+
+    for warning in &recovered.warnings {
+        println!("error: {}", warning);
+    }
+
+    let bad_rows: usize = recovered.warnings.iter().map(|w| w.rows.len()).sum();
+    if bad_rows != 0 {
+        bail!("{} of {} scanlines could not be decoded", bad_rows, height);
+    }
+
+    println!(
+        "ok: {} x {}, all {} scanlines decoded",
+        width, height, height
+    );
+    Ok(())
+}
+
+/// The formats that images can be converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Hdr,
+    Pfm,
+    Ppm,
+    Pgm,
+}
+
+fn output_format(path: &Path) -> Result<OutputFormat> {
+    match extension(path).as_str() {
+        "hdr" | "pic" => Ok(OutputFormat::Hdr),
+        "pfm" => Ok(OutputFormat::Pfm),
+        "ppm" => Ok(OutputFormat::Ppm),
+        "pgm" => Ok(OutputFormat::Pgm),
+        other => bail!("unsupported output format: {:?}", other),
+    }
+}
+
+fn convert(
+    input_path: &Path,
+    output_path: &Path,
+    flat: bool,
+    sixteen_bit: bool,
+    tone_map: ToneMap,
+) -> Result<()> {
+    // Check the output format before anything is read or written
+    let format = output_format(output_path)?;
+
+    let (image, header): (Image, Header) = match extension(input_path).as_str() {
+        "pfm" => {
+            let f = File::open(input_path).context("failed to open specified file")?;
+            let image = pfm::load(BufReader::new(f)).context("failed to load image data")?;
+            (image, Header::default())
+        }
+        _ => {
+            let loader = open(input_path)?;
+            let header = loader.header.clone();
+            let image = loader.load_image().context("failed to load image data")?;
+            (image, header)
+        }
+    };
+    let pixel_aspect = header.pixel_aspect();
+
+    // Only .hdr files can record the pixel aspect ratio, so stretch the image for other formats
+    let image = match format {
+        OutputFormat::Hdr => image,
+        _ => image.resample_square_pixels(pixel_aspect),
+    };
+
+    let f = File::create(output_path).context("failed to create output file")?;
+    let mut f = BufWriter::new(f);
+
+    match format {
+        OutputFormat::Hdr => Encoder::new(&mut f)
+            .run_length_encoding(!flat)
+            .pixel_aspect(pixel_aspect)
+            .header(header)
+            .encode(&image),
+        OutputFormat::Pfm => pfm::Encoder::new(&mut f).encode(&image),
+        OutputFormat::Ppm if sixteen_bit => image.write_ppm16(&mut f, tone_map),
+        OutputFormat::Ppm => image.write_ppm(&mut f, tone_map),
+        OutputFormat::Pgm => image.write_pgm16(&mut f, tone_map),
+    }
+    .context("failed to write image data")?;
+
+    f.flush().context("failed to write image data")?;
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    match Command::from_args() {
+        Command::Info { image_path } => info(&image_path),
+        Command::Validate { image_path } => validate(&image_path),
+        Command::Convert {
+            input_path,
+            output_path,
+            flat,
+            sixteen_bit,
+            exposure,
+            curve,
+        } => convert(
+            &input_path,
+            &output_path,
+            flat,
+            sixteen_bit,
+            ToneMap { exposure, curve },
+        ),
+    }
+}
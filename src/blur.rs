@@ -0,0 +1,264 @@
+//! Gaussian blur, and a bloom pass built on it that scatters the brightest light. Requires the
+//! "std" feature.
+//!
+//! The blur itself is the [`Image::gaussian_blur`] method. This module holds the [`Edges`] it
+//! takes, and the [`Bloom`] operator.
+
+use crate::{Image, Rgb};
+use core::iter::Sum;
+use core::ops::{Add, Mul, Sub};
+
+/// How filters treat pixels beyond the edges of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Edges {
+    /// Repeat the pixels on the edges, so nothing bleeds in from outside the image.
+    #[default]
+    Clamp,
+    /// Wrap around in both directions, for tiling textures.
+    Wrap,
+    /// Wrap around horizontally and clamp vertically, for equirectangular environment maps.
+    Equirectangular,
+}
+
+impl Edges {
+    /// The index of the pixel to use for position `i` along an axis of the given length.
+    fn index(self, i: isize, length: usize, horizontal: bool) -> usize {
+        let wrap = match self {
+            Self::Clamp => false,
+            Self::Wrap => true,
+            Self::Equirectangular => horizontal,
+        };
+
+        if wrap {
+            i.rem_euclid(length as isize) as usize
+        } else {
+            i.clamp(0, length as isize - 1) as usize
+        }
+    }
+}
+
+/// The values that the filters in this module work on: whole pixels, or single channels.
+pub(crate) trait Sample:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self> + Sum
+{
+}
+
+impl Sample for f32 {}
+impl Sample for Rgb {}
+
+impl Image {
+    /// Blur the image with a Gaussian filter whose standard deviation is `sigma` pixels, as two
+    /// separable passes. Requires the "std" feature.
+    pub fn gaussian_blur(&self, sigma: f32, edges: Edges) -> Image {
+        Image {
+            width: self.width,
+            height: self.height,
+            data: gaussian_blur(&self.data, self.width, self.height, sigma, edges),
+        }
+    }
+}
+
+/// Blur a grid of samples, such as a single channel, like [`Image::gaussian_blur`].
+pub(crate) fn gaussian_blur<T: Sample>(
+    data: &[T],
+    width: usize,
+    height: usize,
+    sigma: f32,
+    edges: Edges,
+) -> Vec<T> {
+    if sigma.is_nan() || sigma <= 0. || width == 0 || height == 0 {
+        return data.to_vec();
+    }
+
+    let radius = (3. * sigma).ceil() as isize;
+    let kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| (-(i * i) as f32 / (2. * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = kernel.iter().sum();
+    let kernel: Vec<(isize, f32)> = (-radius..=radius)
+        .zip(kernel.iter().map(|w| w / total))
+        .collect();
+
+    let mut horizontal = Vec::with_capacity(data.len());
+    for row in data.chunks_exact(width) {
+        horizontal.extend((0..width as isize).map(|x| {
+            kernel
+                .iter()
+                .map(|&(i, w)| row[edges.index(x + i, width, true)] * w)
+                .sum::<T>()
+        }));
+    }
+
+    let mut blurred = Vec::with_capacity(data.len());
+    for y in 0..height as isize {
+        blurred.extend((0..width).map(|x| {
+            kernel
+                .iter()
+                .map(|&(i, w)| horizontal[edges.index(y + i, height, false) * width + x] * w)
+                .sum::<T>()
+        }));
+    }
+    blurred
+}
+
+/// A bloom or veiling glare pass, which scatters some of the light from bright areas over their
+/// surroundings the way it scatters in a lens or the eye. Requires the "std" feature.
+///
+/// The light above the threshold is blurred at several scales, each twice as wide as the one
+/// before, which together approximate the long tail of a real point spread function. Light is
+/// moved rather than added, so away from the edges of the image, its total energy does not
+/// change. Apply it to a linear image, before tone mapping.
+/// ```rust
+/// use radiant::blur::{Bloom, Edges};
+/// use radiant::{Image, Rgb};
+///
+/// let mut image = Image { width: 32, height: 32, data: vec![Rgb::zero(); 32 * 32] };
+/// image.data[16 * 32 + 16] = Rgb { r: 100.0, g: 100.0, b: 100.0 };
+///
+/// let bloom = Bloom { threshold: 1.0, ..Bloom::default() };
+/// let glared = bloom.apply(&image, Edges::Clamp);
+/// assert!(glared.data[16 * 32 + 18].g > 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bloom {
+    /// The luminance in cd/m² above which light scatters, see [`Rgb::luminance`]. Negative values
+    /// are treated as zero.
+    pub threshold: f32,
+    /// The fraction of the light above the threshold that scatters.
+    pub intensity: f32,
+    /// The standard deviation of the narrowest blur, in pixels.
+    pub radius: f32,
+    /// How many scales to blur at. Each one doubles the width of the glare.
+    pub levels: usize,
+}
+
+impl Default for Bloom {
+    /// Scatter 5% of the light above 1000 cd/m², over five scales starting at 2 pixels.
+    fn default() -> Self {
+        Self {
+            threshold: 1000.,
+            intensity: 0.05,
+            radius: 2.,
+            levels: 5,
+        }
+    }
+}
+
+impl Bloom {
+    /// Apply the glare to an image.
+    pub fn apply(&self, image: &Image, edges: Edges) -> Image {
+        let (width, height) = (image.width, image.height);
+
+        // The part of each pixel above the threshold, keeping its colour. A negative threshold
+        // counts as zero, so that black pixels don't divide zero by zero.
+        let threshold = self.threshold.max(0.);
+        let mut bright = image.clone();
+        bright.map(|rgb| {
+            let luminance = rgb.luminance();
+            if luminance > threshold && luminance.is_finite() {
+                rgb * ((luminance - threshold) / luminance)
+            } else {
+                Rgb::zero()
+            }
+        });
+
+        let mut glare = Image {
+            width,
+            height,
+            data: vec![Rgb::zero(); width * height],
+        };
+        let (mut level_width, mut level_height) = (width, height);
+        let mut level = bright.data.clone();
+        let levels = self.levels.max(1);
+        for i in 0..levels {
+            if i > 0 {
+                (level_width, level_height, level) =
+                    downsample(&level, level_width, level_height, edges);
+            }
+            let blurred = gaussian_blur(&level, level_width, level_height, self.radius, edges);
+            glare.add(&Image {
+                width,
+                height,
+                data: resize(&blurred, level_width, level_height, width, height, edges),
+            });
+        }
+
+        let mut result = image.clone();
+        result.zip_map(&bright, |rgb, bright| rgb - bright * self.intensity);
+        result.zip_map(&glare, |rgb, glare| {
+            rgb + glare * (self.intensity / levels as f32)
+        });
+        result
+    }
+}
+
+/// Halve the size of a grid of samples by averaging blocks of 2 by 2, returning the new width,
+/// height and samples.
+pub(crate) fn downsample<T: Sample>(
+    data: &[T],
+    width: usize,
+    height: usize,
+    edges: Edges,
+) -> (usize, usize, Vec<T>) {
+    let (new_width, new_height) = (width.div_ceil(2), height.div_ceil(2));
+    let mut downsampled = Vec::with_capacity(new_width * new_height);
+
+    for y in 0..new_height as isize {
+        downsampled.extend((0..new_width as isize).map(|x| {
+            let sum = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                .iter()
+                .map(|&(dx, dy)| {
+                    let sx = edges.index(2 * x + dx, width, true);
+                    let sy = edges.index(2 * y + dy, height, false);
+                    data[sy * width + sx]
+                })
+                .sum::<T>();
+            sum * 0.25
+        }));
+    }
+
+    (new_width, new_height, downsampled)
+}
+
+/// Resize a grid of samples with bilinear interpolation, lining up the centers of the samples.
+pub(crate) fn resize<T: Sample>(
+    data: &[T],
+    width: usize,
+    height: usize,
+    new_width: usize,
+    new_height: usize,
+    edges: Edges,
+) -> Vec<T> {
+    if (width, height) == (new_width, new_height) {
+        return data.to_vec();
+    }
+
+    // The two source samples and the weight of the second one, along one axis
+    let taps = |i: usize, length: usize, new_length: usize, horizontal: bool| {
+        let position = (i as f32 + 0.5) * length as f32 / new_length as f32 - 0.5;
+        let first = position.floor();
+        let t = position - first;
+        let first = first as isize;
+        (
+            edges.index(first, length, horizontal),
+            edges.index(first + 1, length, horizontal),
+            t,
+        )
+    };
+    let lerp = |a: T, b: T, t: f32| a + (b - a) * t;
+    let sample = |x: usize, y: usize| data[y * width + x];
+
+    let columns: Vec<_> = (0..new_width)
+        .map(|x| taps(x, width, new_width, true))
+        .collect();
+    let mut resized = Vec::with_capacity(new_width * new_height);
+    for y in 0..new_height {
+        let (top, bottom, ty) = taps(y, height, new_height, false);
+        resized.extend(columns.iter().map(|&(left, right, tx)| {
+            let upper = lerp(sample(left, top), sample(right, top), tx);
+            let lower = lerp(sample(left, bottom), sample(right, bottom), tx);
+            lerp(upper, lower, ty)
+        }));
+    }
+    resized
+}
@@ -0,0 +1,297 @@
+//! Assemble a high dynamic range [`Image`] from bracketed 8-bit exposures of a static scene, like
+//! a light probe photographed at several shutter speeds. Requires the "std" feature.
+//!
+//! The response curve of the camera is recovered with the method of Debevec and Malik, "Recovering
+//! High Dynamic Range Radiance Maps from Photographs" (1997), and the exposures are merged with a
+//! hat-shaped weighting function that trusts the middle of the range the most. The result is in
+//! relative units, and can be saved with [`crate::Encoder`].
+//! ```rust
+//! use radiant::bracketing::{Exposure, Response};
+//!
+//! # let (width, height) = (16, 16);
+//! # let shot = |time: f32| (0..width * height)
+//! #     .map(|i| [((i as f32 * time).min(255.)) as u8; 3])
+//! #     .collect();
+//! let exposures = [
+//!     Exposure { width, height, data: shot(0.25), time: 0.25 },
+//!     Exposure { width, height, data: shot(1.0), time: 1.0 },
+//!     Exposure { width, height, data: shot(4.0), time: 4.0 },
+//! ];
+//!
+//! let response = Response::recover(&exposures, 20.0);
+//! let image = response.merge(&exposures);
+//!
+//! let mut file = Vec::new();
+//! radiant::Encoder::new(&mut file).encode(&image).expect("failed to encode image");
+//! ```
+
+use crate::{Image, Rgb};
+
+/// One photograph of a bracketed sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exposure {
+    /// The width of the photograph.
+    pub width: usize,
+    /// The height of the photograph.
+    pub height: usize,
+    /// The 8-bit RGB pixels, from the top left, row by row. They should not be tone mapped beyond
+    /// what the camera itself does.
+    pub data: Vec<[u8; 3]>,
+    /// The exposure time in seconds, or anything else proportional to the amount of light the
+    /// photograph collected, such as time divided by the f-number squared.
+    pub time: f32,
+}
+
+/// The response curve of a camera, which maps each 8-bit pixel value back to the exposure that
+/// produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    /// The natural logarithm of the exposure that produces each pixel value, for the red, green
+    /// and blue channels. Only the differences between values are meaningful.
+    pub curves: [[f32; 256]; 3],
+}
+
+impl Response {
+    /// The response of a camera whose pixel values are proportional to the exposure, such as raw
+    /// sensor data. Zero is treated as half of the smallest step.
+    pub fn linear() -> Self {
+        let mut curve = [0.; 256];
+        for (z, g) in curve.iter_mut().enumerate() {
+            *g = (z as f32).max(0.5).ln();
+        }
+        Self { curves: [curve; 3] }
+    }
+
+    /// Recover the response curve from a bracketed sequence with the least-squares method of
+    /// Debevec and Malik. `smoothness` weighs the second derivative of the curve against fitting
+    /// the samples; values around 10 to 100 work well for photographs with a little noise.
+    ///
+    /// Pixels are sampled from the middle exposure so that every pixel value that occurs in it is
+    /// covered. Pixel values that never occur are filled in by the smoothness term, so the result
+    /// is only reliable if the exposures span the whole range between black and white.
+    ///
+    /// Panics if there are fewer than two exposures, or if they don't all have the same
+    /// dimensions and positive exposure times.
+    pub fn recover(exposures: &[Exposure], smoothness: f32) -> Self {
+        check_exposures(exposures);
+        assert!(
+            exposures.len() >= 2,
+            "recovering a response curve takes at least two exposures"
+        );
+
+        let mut sorted: Vec<&Exposure> = exposures.iter().collect();
+        sorted.sort_by(|a, b| a.time.total_cmp(&b.time));
+        let middle = sorted[sorted.len() / 2];
+
+        let mut curves = [[0.; 256]; 3];
+        for (channel, curve) in curves.iter_mut().enumerate() {
+            // The first pixel with each value in the middle exposure
+            let mut samples = [None; 256];
+            for (i, pixel) in middle.data.iter().enumerate() {
+                samples[pixel[channel] as usize].get_or_insert(i);
+            }
+            let samples: Vec<usize> = samples.iter().flatten().copied().collect();
+
+            let solution = solve_response(exposures, channel, &samples, smoothness as f64);
+            for (g, value) in curve.iter_mut().zip(solution) {
+                *g = value as f32;
+            }
+        }
+
+        Self { curves }
+    }
+
+    /// Merge a bracketed sequence into a single image, averaging the exposure each photograph
+    /// implies for every pixel with a hat-shaped weight that is zero for black and white.
+    ///
+    /// Pixels that are black or white in every exposure take their value from the longest or
+    /// shortest exposure respectively, so they are clipped rather than lost.
+    ///
+    /// Panics if there are no exposures, or if they don't all have the same dimensions and positive
+    /// exposure times.
+    pub fn merge(&self, exposures: &[Exposure]) -> Image {
+        check_exposures(exposures);
+
+        let shortest = exposures
+            .iter()
+            .min_by(|a, b| a.time.total_cmp(&b.time))
+            .expect("merging takes at least one exposure");
+        let longest = exposures
+            .iter()
+            .max_by(|a, b| a.time.total_cmp(&b.time))
+            .expect("merging takes at least one exposure");
+
+        let &Exposure { width, height, .. } = shortest;
+        let data = (0..width * height)
+            .map(|i| {
+                let [r, g, b] = [0, 1, 2].map(|channel| {
+                    let curve = &self.curves[channel];
+                    let log_exposure = |exposure: &Exposure| {
+                        curve[exposure.data[i][channel] as usize] - exposure.time.ln()
+                    };
+
+                    let (mut sum, mut total_weight) = (0., 0.);
+                    for exposure in exposures {
+                        let w = weight(exposure.data[i][channel]);
+                        sum += w * log_exposure(exposure);
+                        total_weight += w;
+                    }
+
+                    if total_weight > 0. {
+                        (sum / total_weight).exp()
+                    } else if shortest.data[i][channel] > 127 {
+                        log_exposure(shortest).exp()
+                    } else {
+                        log_exposure(longest).exp()
+                    }
+                });
+                Rgb { r, g, b }
+            })
+            .collect();
+
+        Image {
+            width,
+            height,
+            data,
+        }
+    }
+}
+
+/// The hat-shaped weight of a pixel value, which peaks in the middle of the range and is zero for
+/// black and white.
+fn weight(z: u8) -> f32 {
+    if z <= 127 {
+        z as f32
+    } else {
+        (255 - z) as f32
+    }
+}
+
+fn check_exposures(exposures: &[Exposure]) {
+    if let Some(first) = exposures.first() {
+        assert!(
+            exposures.iter().all(|exposure| {
+                exposure.width == first.width
+                    && exposure.height == first.height
+                    && exposure.data.len() == first.width * first.height
+            }),
+            "the exposures must have the same dimensions"
+        );
+        assert!(
+            exposures.iter().all(|exposure| exposure.time > 0.),
+            "the exposure times must be positive"
+        );
+    }
+}
+
+/// Solve the least-squares system of Debevec and Malik for one channel, returning the curve.
+///
+/// The unknowns are the 256 values of the curve and the log exposure of every sampled pixel. The
+/// normal equations are accumulated directly, and because every sample only appears in its own
+/// rows, the sample unknowns are eliminated with a Schur complement, leaving a 256 by 256 system.
+fn solve_response(
+    exposures: &[Exposure],
+    channel: usize,
+    samples: &[usize],
+    smoothness: f64,
+) -> Vec<f64> {
+    const N: usize = 256;
+    let mut matrix = vec![0_f64; N * N];
+    let mut rhs = vec![0_f64; N];
+
+    for &i in samples {
+        // Rows of the form w * g(z) - w * ln(E) = w * ln(t)
+        let mut row: Vec<(usize, f64, f64)> = Vec::with_capacity(exposures.len());
+        for exposure in exposures {
+            let z = exposure.data[i][channel];
+            let w = weight(z) as f64;
+            if w > 0. {
+                row.push((z as usize, w * w, (exposure.time as f64).ln()));
+            }
+        }
+
+        // The sample's own diagonal entry and right-hand side, before elimination
+        let diagonal: f64 = row.iter().map(|&(_, ww, _)| ww).sum();
+        if diagonal == 0. {
+            continue;
+        }
+        let own_rhs: f64 = row.iter().map(|&(_, ww, log_time)| -ww * log_time).sum();
+
+        for &(z, ww, log_time) in &row {
+            matrix[z * N + z] += ww;
+            rhs[z] += ww * log_time;
+        }
+
+        // Subtract the coupling through the eliminated unknown, whose column holds -w² entries
+        for &(a, wa, _) in &row {
+            rhs[a] -= -wa * own_rhs / diagonal;
+            for &(b, wb, _) in &row {
+                matrix[a * N + b] -= wa * wb / diagonal;
+            }
+        }
+    }
+
+    // Fix the middle of the curve at zero, since only differences are determined
+    matrix[128 * N + 128] += 1.;
+
+    // Penalize the second derivative of the curve
+    for z in 1..N - 1 {
+        let w = smoothness * weight(z as u8) as f64;
+        let taps = [(z - 1, w), (z, -2. * w), (z + 1, w)];
+        for &(a, va) in &taps {
+            for &(b, vb) in &taps {
+                matrix[a * N + b] += va * vb;
+            }
+        }
+    }
+
+    solve(&mut matrix, &mut rhs);
+    rhs
+}
+
+/// Solve the linear system in place with Gaussian elimination and partial pivoting, leaving the
+/// solution in `rhs`. Unconstrained unknowns are set to zero.
+fn solve(matrix: &mut [f64], rhs: &mut [f64]) {
+    let n = rhs.len();
+
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&a, &b| {
+                let (a, b) = (matrix[a * n + column].abs(), matrix[b * n + column].abs());
+                a.total_cmp(&b)
+            })
+            .unwrap_or(column);
+        if matrix[pivot * n + column].abs() < 1e-12 {
+            continue;
+        }
+
+        if pivot != column {
+            for k in 0..n {
+                matrix.swap(pivot * n + k, column * n + k);
+            }
+            rhs.swap(pivot, column);
+        }
+
+        let diagonal = matrix[column * n + column];
+        for row in column + 1..n {
+            let factor = matrix[row * n + column] / diagonal;
+            if factor == 0. {
+                continue;
+            }
+            for k in column..n {
+                matrix[row * n + k] -= factor * matrix[column * n + k];
+            }
+            rhs[row] -= factor * rhs[column];
+        }
+    }
+
+    for row in (0..n).rev() {
+        let diagonal = matrix[row * n + row];
+        if diagonal.abs() < 1e-12 {
+            rhs[row] = 0.;
+            continue;
+        }
+        let sum: f64 = (row + 1..n).map(|k| matrix[row * n + k] * rhs[k]).sum();
+        rhs[row] = (rhs[row] - sum) / diagonal;
+    }
+}
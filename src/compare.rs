@@ -0,0 +1,269 @@
+//! Error metrics between two images, for comparing rendered images against reference images.
+//! Requires the "std" feature.
+//!
+//! Every function takes the image under test first and the reference image second, and panics
+//! if their dimensions differ.
+//! ```rust
+//! use radiant::compare::{self, Tolerance};
+//! use radiant::{Image, Rgb};
+//!
+//! let reference = Image { width: 1, height: 1, data: vec![Rgb { r: 1.0, g: 1.0, b: 1.0 }] };
+//! let rendered = Image { width: 1, height: 1, data: vec![Rgb { r: 1.01, g: 1.0, b: 1.0 }] };
+//!
+//! let tolerance = Tolerance { relative_error: Some(0.01), ..Tolerance::default() };
+//! compare::assert_close(&rendered, &reference, &tolerance);
+//! ```
+
+use crate::{Image, Rgb};
+use std::fmt;
+
+/// Every metric computed by [`metrics`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// The mean squared error, see [`mse`].
+    pub mse: f32,
+    /// The root mean squared error, see [`rmse`].
+    pub rmse: f32,
+    /// The peak signal-to-noise ratio in log space, in decibels, see [`log_psnr`].
+    pub log_psnr: f32,
+    /// The mean relative error, see [`relative_error`].
+    pub relative_error: f32,
+    /// The mean perceptual error, see [`flip`].
+    pub flip: f32,
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "MSE {}, RMSE {}, log PSNR {} dB, relative error {}, FLIP {}",
+            self.mse, self.rmse, self.log_psnr, self.relative_error, self.flip
+        )
+    }
+}
+
+/// Limits on the [`Metrics`] of a comparison. Metrics that are `None` are not checked.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Tolerance {
+    /// The largest acceptable root mean squared error.
+    pub rmse: Option<f32>,
+    /// The smallest acceptable peak signal-to-noise ratio in log space, in decibels.
+    pub log_psnr: Option<f32>,
+    /// The largest acceptable mean relative error.
+    pub relative_error: Option<f32>,
+    /// The largest acceptable mean perceptual error.
+    pub flip: Option<f32>,
+}
+
+impl Metrics {
+    /// Whether every metric is within the given tolerance.
+    pub fn passes(&self, tolerance: &Tolerance) -> bool {
+        let below = |value: f32, limit: Option<f32>| limit.is_none_or(|limit| value <= limit);
+
+        below(self.rmse, tolerance.rmse)
+            && tolerance
+                .log_psnr
+                .is_none_or(|limit| self.log_psnr >= limit)
+            && below(self.relative_error, tolerance.relative_error)
+            && below(self.flip, tolerance.flip)
+    }
+}
+
+/// Compare two images, and panic with their metrics if they are not within the given tolerance.
+/// This is meant to be used in tests.
+pub fn assert_close(image: &Image, reference: &Image, tolerance: &Tolerance) {
+    let metrics = metrics(image, reference);
+    if !metrics.passes(tolerance) {
+        panic!(
+            "the images differ by more than the tolerance\n  metrics:   {}\n  tolerance: {:?}",
+            metrics, tolerance
+        );
+    }
+}
+
+/// Compute every metric at once.
+pub fn metrics(image: &Image, reference: &Image) -> Metrics {
+    let mse = mse(image, reference);
+    Metrics {
+        mse,
+        rmse: mse.sqrt(),
+        log_psnr: log_psnr(image, reference),
+        relative_error: relative_error(image, reference),
+        flip: flip(image, reference),
+    }
+}
+
+/// The mean squared difference between the channels of the two images.
+pub fn mse(image: &Image, reference: &Image) -> f32 {
+    mean_channels(image, reference, |a, b| (a - b) * (a - b))
+}
+
+/// The square root of the [`mse`].
+pub fn rmse(image: &Image, reference: &Image) -> f32 {
+    mse(image, reference).sqrt()
+}
+
+/// The peak signal-to-noise ratio in decibels, computed on `log2(1 + x)` of every channel so that
+/// dark and bright regions weigh more evenly. The peak is the largest value in the reference.
+/// Identical images give infinity.
+pub fn log_psnr(image: &Image, reference: &Image) -> f32 {
+    let log = |c: f32| c.max(0.).ln_1p() * std::f32::consts::LOG2_E;
+    let mse = mean_channels(image, reference, |a, b| (log(a) - log(b)).powi(2));
+    let peak = reference
+        .data
+        .iter()
+        .map(|rgb| log(rgb.r.max(rgb.g).max(rgb.b)))
+        .fold(0., f32::max);
+
+    if mse == 0. {
+        f32::INFINITY
+    } else {
+        10. * (peak * peak / mse).log10()
+    }
+}
+
+/// The mean of `|a - b| / (|b| + 0.01)` over every channel, where `b` is the reference. The small
+/// offset keeps black reference pixels from dominating it.
+pub fn relative_error(image: &Image, reference: &Image) -> f32 {
+    mean_channels(image, reference, |a, b| (a - b).abs() / (b.abs() + 0.01))
+}
+
+/// The mean of the per-pixel perceptual error given by [`flip_errors`].
+pub fn flip(image: &Image, reference: &Image) -> f32 {
+    let errors = flip_errors(image, reference);
+    errors.iter().sum::<f32>() / errors.len().max(1) as f32
+}
+
+/// A perceptual error between 0 and 1 for every pixel, loosely modelled on HDR-FLIP. Both images
+/// are tone mapped at five exposures spanning four stops around the log-average luminance of the
+/// reference, and the largest colour difference in CIELAB space is kept for each pixel. Unlike
+/// FLIP, there is no spatial filtering, so isolated noisy pixels are not forgiven.
+pub fn flip_errors(image: &Image, reference: &Image) -> Vec<f32> {
+    check_dimensions(image, reference);
+
+    let count = reference.data.len().max(1) as f32;
+    let log_average = reference
+        .data
+        .iter()
+        .map(|&rgb| (1e-4 + rgb.relative_luminance().max(0.)).ln())
+        .sum::<f32>()
+        / count;
+    let key = 0.18 / log_average.exp();
+
+    image
+        .data
+        .iter()
+        .zip(&reference.data)
+        .map(|(&a, &b)| {
+            (-2..=2)
+                .map(|stop| {
+                    let scale = key * (stop as f32).exp2();
+                    let (a, b) = (lab(a, scale), lab(b, scale));
+                    let delta_e = a
+                        .iter()
+                        .zip(&b)
+                        .map(|(a, b)| (a - b) * (a - b))
+                        .sum::<f32>()
+                        .sqrt();
+                    (delta_e / 100.).powf(0.7).min(1.)
+                })
+                .fold(0., f32::max)
+        })
+        .collect()
+}
+
+/// A false-colour image of the [`flip_errors`], going from black for no error through purple and
+/// orange to pale yellow for the largest error.
+pub fn difference(image: &Image, reference: &Image) -> Image {
+    let data = flip_errors(image, reference)
+        .into_iter()
+        .map(heat)
+        .collect();
+
+    Image {
+        width: reference.width,
+        height: reference.height,
+        data,
+    }
+}
+
+fn check_dimensions(image: &Image, reference: &Image) {
+    assert!(
+        image.width == reference.width
+            && image.height == reference.height
+            && image.data.len() == reference.data.len(),
+        "the images must have the same dimensions"
+    );
+}
+
+fn mean_channels(image: &Image, reference: &Image, error: impl Fn(f32, f32) -> f32) -> f32 {
+    check_dimensions(image, reference);
+
+    let sum: f64 = image
+        .data
+        .iter()
+        .zip(&reference.data)
+        .map(|(a, b)| (error(a.r, b.r) + error(a.g, b.g) + error(a.b, b.b)) as f64)
+        .sum();
+    (sum / (3 * image.data.len()).max(1) as f64) as f32
+}
+
+/// Tone map a linear pixel with the given exposure scale, and convert it to CIELAB.
+fn lab(rgb: Rgb, scale: f32) -> [f32; 3] {
+    // Compress highlights into [0, 1) without changing hue
+    let map = |c: f32| {
+        let c = (c * scale).max(0.);
+        c / (1. + c)
+    };
+    let (r, g, b) = (map(rgb.r), map(rgb.g), map(rgb.b));
+
+    // Linear sRGB to XYZ, relative to the D65 white point
+    let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+
+    let f = |t: f32| {
+        if t > 216. / 24389. {
+            t.cbrt()
+        } else {
+            (24389. / 27. * t + 16.) / 116.
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116. * fy - 16., 500. * (fx - fy), 200. * (fy - fz)]
+}
+
+/// Map a value between 0 and 1 onto a black, purple, orange and yellow heat palette.
+fn heat(t: f32) -> Rgb {
+    const STOPS: [Rgb; 5] = [
+        Rgb {
+            r: 0.,
+            g: 0.,
+            b: 0.,
+        },
+        Rgb {
+            r: 0.23,
+            g: 0.06,
+            b: 0.44,
+        },
+        Rgb {
+            r: 0.72,
+            g: 0.21,
+            b: 0.47,
+        },
+        Rgb {
+            r: 0.99,
+            g: 0.53,
+            b: 0.38,
+        },
+        Rgb {
+            r: 0.99,
+            g: 0.99,
+            b: 0.75,
+        },
+    ];
+
+    let t = if t > 0. { t.min(1.) } else { 0. } * (STOPS.len() - 1) as f32;
+    let i = (t as usize).min(STOPS.len() - 2);
+    STOPS[i].lerp(STOPS[i + 1], t - i as f32)
+}
@@ -0,0 +1,239 @@
+//! Conversion of planar RGBE bytes to floating point pixels, with SIMD implementations where the
+//! target supports them.
+
+use crate::{exposure_scale, Rgb};
+
+/// The factor that each mantissa is scaled by, for every possible exponent.
+static EXPOSURES: [f32; 256] = exposure_table();
+
+const fn exposure_table() -> [f32; 256] {
+    let mut table = [0.; 256];
+    let mut e = 0;
+    while e < 256 {
+        table[e] = exposure_scale(e as u8) / 255.;
+        e += 1;
+    }
+    table
+}
+
+/// Convert one scanline of planar RGBE data, given as separate red, green, blue and exponent
+/// channels, into pixels. All channels must be as long as the output.
+pub(crate) fn rgbe_to_rgb([r, g, b, e]: [&[u8]; 4], out: &mut [Rgb]) {
+    let len = out.len();
+    let (r, g, b, e) = (&r[..len], &g[..len], &b[..len], &e[..len]);
+
+    let done = convert_simd([r, g, b, e], out);
+    convert_scalar(
+        [&r[done..], &g[done..], &b[done..], &e[done..]],
+        &mut out[done..],
+    );
+}
+
+fn convert_scalar([r, g, b, e]: [&[u8]; 4], out: &mut [Rgb]) {
+    for (i, pixel) in out.iter_mut().enumerate() {
+        let d = EXPOSURES[e[i] as usize];
+        *pixel = Rgb {
+            r: r[i] as f32 * d,
+            g: g[i] as f32 * d,
+            b: b[i] as f32 * d,
+        };
+    }
+}
+
+/// Convert as many pixels as the best available instruction set handles in whole batches,
+/// returning how many were converted.
+#[allow(unreachable_code)]
+fn convert_simd(planes: [&[u8]; 4], out: &mut [Rgb]) -> usize {
+    #[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // Safety: the CPU supports AVX2
+            return unsafe { x86::convert_avx2(planes, out) };
+        }
+        if is_x86_feature_detected!("sse2") {
+            // Safety: the CPU supports SSE2
+            return unsafe { x86::convert_sse2(planes, out) };
+        }
+    }
+
+    #[cfg(all(
+        not(feature = "std"),
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "avx2"
+    ))]
+    {
+        // Safety: the target always supports AVX2
+        return unsafe { x86::convert_avx2(planes, out) };
+    }
+
+    #[cfg(all(
+        not(feature = "std"),
+        any(target_arch = "x86", target_arch = "x86_64"),
+        not(target_feature = "avx2"),
+        target_feature = "sse2"
+    ))]
+    {
+        // Safety: the target always supports SSE2
+        return unsafe { x86::convert_sse2(planes, out) };
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        // Safety: the target always supports NEON
+        return unsafe { neon::convert(planes, out) };
+    }
+
+    let _ = (planes, out);
+    0
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    use super::EXPOSURES;
+    use crate::Rgb;
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    /// Interleave four red, four green and four blue values into four pixels, and store them.
+    #[inline(always)]
+    unsafe fn store_interleaved(out: *mut f32, r: __m128, g: __m128, b: __m128) {
+        let rg_lo = _mm_unpacklo_ps(r, g); // r0 g0 r1 g1
+        let rg_hi = _mm_unpackhi_ps(r, g); // r2 g2 r3 g3
+        let b_lo = _mm_shuffle_ps(b, rg_lo, 0b11_10_01_00); // b0 b1 r1 g1
+        let b_hi = _mm_shuffle_ps(b, rg_hi, 0b11_10_11_10); // b2 b3 r3 g3
+
+        _mm_storeu_ps(out, _mm_shuffle_ps(rg_lo, b_lo, 0b10_00_01_00)); // r0 g0 b0 r1
+        _mm_storeu_ps(out.add(4), _mm_shuffle_ps(b_lo, rg_hi, 0b01_00_01_11)); // g1 b1 r2 g2
+        _mm_storeu_ps(out.add(8), _mm_shuffle_ps(b_hi, b_hi, 0b01_11_10_00)); // b2 r3 g3 b3
+    }
+
+    #[cfg(any(feature = "std", not(target_feature = "avx2")))]
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn convert_sse2([r, g, b, e]: [&[u8]; 4], out: &mut [Rgb]) -> usize {
+        const LANES: usize = 4;
+        let batches = out.len() / LANES;
+        let out_ptr = out.as_mut_ptr() as *mut f32;
+        let zero = _mm_setzero_si128();
+
+        let load = |channel: &[u8], i: usize| -> __m128 {
+            let mut bytes = [0u8; LANES];
+            bytes.copy_from_slice(&channel[i..i + LANES]);
+            let bytes = _mm_cvtsi32_si128(i32::from_le_bytes(bytes));
+            let words = _mm_unpacklo_epi8(bytes, zero);
+            _mm_cvtepi32_ps(_mm_unpacklo_epi16(words, zero))
+        };
+
+        for batch in 0..batches {
+            let i = batch * LANES;
+            let d = _mm_set_ps(
+                EXPOSURES[e[i + 3] as usize],
+                EXPOSURES[e[i + 2] as usize],
+                EXPOSURES[e[i + 1] as usize],
+                EXPOSURES[e[i] as usize],
+            );
+
+            store_interleaved(
+                out_ptr.add(i * 3),
+                _mm_mul_ps(load(r, i), d),
+                _mm_mul_ps(load(g, i), d),
+                _mm_mul_ps(load(b, i), d),
+            );
+        }
+
+        batches * LANES
+    }
+
+    #[cfg(any(feature = "std", target_feature = "avx2"))]
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn convert_avx2([r, g, b, e]: [&[u8]; 4], out: &mut [Rgb]) -> usize {
+        const LANES: usize = 8;
+        let batches = out.len() / LANES;
+        let out_ptr = out.as_mut_ptr() as *mut f32;
+
+        let load = |channel: &[u8], i: usize| -> __m256i {
+            let bytes = &channel[i..i + LANES];
+            _mm256_cvtepu8_epi32(_mm_loadl_epi64(bytes.as_ptr() as *const __m128i))
+        };
+
+        for batch in 0..batches {
+            let i = batch * LANES;
+            let d = _mm256_i32gather_ps::<4>(EXPOSURES.as_ptr(), load(e, i));
+            let r = _mm256_mul_ps(_mm256_cvtepi32_ps(load(r, i)), d);
+            let g = _mm256_mul_ps(_mm256_cvtepi32_ps(load(g, i)), d);
+            let b = _mm256_mul_ps(_mm256_cvtepi32_ps(load(b, i)), d);
+
+            let out = out_ptr.add(i * 3);
+            store_interleaved(
+                out,
+                _mm256_castps256_ps128(r),
+                _mm256_castps256_ps128(g),
+                _mm256_castps256_ps128(b),
+            );
+            store_interleaved(
+                out.add(12),
+                _mm256_extractf128_ps::<1>(r),
+                _mm256_extractf128_ps::<1>(g),
+                _mm256_extractf128_ps::<1>(b),
+            );
+        }
+
+        batches * LANES
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon {
+    use super::EXPOSURES;
+    use crate::Rgb;
+    use core::arch::aarch64::*;
+
+    pub(super) unsafe fn convert([r, g, b, e]: [&[u8]; 4], out: &mut [Rgb]) -> usize {
+        const LANES: usize = 8;
+        let batches = out.len() / LANES;
+        let out_ptr = out.as_mut_ptr() as *mut f32;
+
+        let load = |channel: &[u8], i: usize| -> (float32x4_t, float32x4_t) {
+            let bytes = &channel[i..i + LANES];
+            let words = vmovl_u8(vld1_u8(bytes.as_ptr()));
+            (
+                vcvtq_f32_u32(vmovl_u16(vget_low_u16(words))),
+                vcvtq_f32_u32(vmovl_u16(vget_high_u16(words))),
+            )
+        };
+
+        for batch in 0..batches {
+            let i = batch * LANES;
+            let mut d = [0f32; LANES];
+            for (d, &e) in d.iter_mut().zip(&e[i..i + LANES]) {
+                *d = EXPOSURES[e as usize];
+            }
+            let (d_lo, d_hi) = (vld1q_f32(d.as_ptr()), vld1q_f32(d.as_ptr().add(4)));
+
+            let (r_lo, r_hi) = load(r, i);
+            let (g_lo, g_hi) = load(g, i);
+            let (b_lo, b_hi) = load(b, i);
+
+            let out = out_ptr.add(i * 3);
+            vst3q_f32(
+                out,
+                float32x4x3_t(
+                    vmulq_f32(r_lo, d_lo),
+                    vmulq_f32(g_lo, d_lo),
+                    vmulq_f32(b_lo, d_lo),
+                ),
+            );
+            vst3q_f32(
+                out.add(12),
+                float32x4x3_t(
+                    vmulq_f32(r_hi, d_hi),
+                    vmulq_f32(g_hi, d_hi),
+                    vmulq_f32(b_hi, d_hi),
+                ),
+            );
+        }
+
+        batches * LANES
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{Header, Image, Rgb};
+use std::io::{Error as IoError, ErrorKind, Write};
+
+/// Encodes images in the Radiance HDR format. Requires the "std" feature.
+/// ```rust
+/// use radiant::{Encoder, Image, Rgb};
+///
+/// let image = Image {
+///     width: 2,
+///     height: 1,
+///     data: vec![Rgb { r: 1.0, g: 0.5, b: 0.25 }; 2],
+/// };
+///
+/// let mut file = Vec::new();
+/// Encoder::new(&mut file).encode(&image).expect("failed to encode image");
+/// ```
+pub struct Encoder<W> {
+    writer: W,
+    run_length_encoding: bool,
+    pixel_aspect: f32,
+    header: Header,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Construct a new [`Encoder`], which uses run-length encoding by default.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            run_length_encoding: true,
+            pixel_aspect: 1.,
+            header: Header::default(),
+        }
+    }
+
+    /// Choose whether scanlines are run-length encoded, or stored as flat RGBE pixels. Images
+    /// narrower than 8 or wider than 32767 pixels are always stored flat, because the run-length
+    /// encoding can't represent them.
+    pub fn run_length_encoding(mut self, enabled: bool) -> Self {
+        self.run_length_encoding = enabled;
+        self
+    }
+
+    /// Set the ratio of the height of a pixel to its width, which is written to the header as the
+    /// `PIXASPECT` variable unless it is 1. Like [`crate::Header::pixel_aspect`], values that
+    /// aren't positive and finite are ignored, so the pixels stay square.
+    pub fn pixel_aspect(mut self, pixel_aspect: f32) -> Self {
+        self.pixel_aspect = if pixel_aspect > 0. && pixel_aspect.is_finite() {
+            pixel_aspect
+        } else {
+            1.
+        };
+        self
+    }
+
+    /// Copy the variables and other lines of a [`Header`] into the header that is written, e.g. to
+    /// keep the `EXPOSURE` and `VIEW` of an image that was loaded. `FORMAT` and `PIXASPECT` are
+    /// left out, because the encoder writes those itself. Lines that contain a line break make
+    /// encoding fail with an error of the kind [`std::io::ErrorKind::InvalidInput`], and empty
+    /// lines are skipped, because either would end the header early.
+    pub fn header(mut self, header: Header) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Write the header for an image of the given dimensions, and convert this encoder into a
+    /// [`ScanlinesEncoder`], which lets you write the image one scanline at a time.
+    pub fn scanlines(
+        mut self,
+        width: usize,
+        height: usize,
+    ) -> Result<ScanlinesEncoder<W>, IoError> {
+        let Header { variables, other } = &self.header;
+        let mut lines = other
+            .iter()
+            .chain(variables.iter().flat_map(|(n, v)| [n, v]));
+        if lines.any(|line| line.contains('\n')) {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "header lines must not contain line breaks",
+            ));
+        }
+
+        writeln!(self.writer, "#?RADIANCE")?;
+        for line in other.iter().filter(|line| !line.trim().is_empty()) {
+            writeln!(self.writer, "{}", line)?;
+        }
+        writeln!(self.writer, "FORMAT=32-bit_rle_rgbe")?;
+        for (name, value) in variables {
+            if !matches!(name.as_str(), "FORMAT" | "PIXASPECT") {
+                writeln!(self.writer, "{}={}", name, value)?;
+            }
+        }
+        if self.pixel_aspect != 1. {
+            writeln!(self.writer, "PIXASPECT={}", self.pixel_aspect)?;
+        }
+        writeln!(self.writer, "\n-Y {} +X {}", height, width)?;
+
+        Ok(ScanlinesEncoder {
+            width,
+            height,
+            writer: self.writer,
+            run_length_encoding: self.run_length_encoding && (8..=0x7fff).contains(&width),
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Encode an entire [`Image`] at once. If the length of its data doesn't match its
+    /// dimensions, an error of the kind [`std::io::ErrorKind::InvalidInput`] will be returned.
+    pub fn encode(self, image: &Image) -> Result<(), IoError> {
+        let &Image { width, height, .. } = image;
+        if width.checked_mul(height) != Some(image.data.len()) {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "the image data does not match its dimensions",
+            ));
+        }
+
+        let mut scanlines = self.scanlines(width, height)?;
+        for scanline in image.data.chunks_exact(width.max(1)) {
+            scanlines.write_scanline(scanline)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An image encoder that writes images line by line, from top to bottom. Get one from
+/// [`Encoder::scanlines`].
+pub struct ScanlinesEncoder<W> {
+    /// The width of the image.
+    pub width: usize,
+    /// The height of the image, i.e. the number of scanlines.
+    pub height: usize,
+    writer: W,
+    run_length_encoding: bool,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> ScanlinesEncoder<W> {
+    /// Encode the next horizontal scanline of the image. The provided scanline must be at least as
+    /// long as the width of the image, otherwise an error of the kind
+    /// [`std::io::ErrorKind::InvalidInput`] will be returned.
+    pub fn write_scanline(&mut self, scanline: &[Rgb]) -> Result<(), IoError> {
+        let scanline = scanline.get(..self.width).ok_or_else(|| {
+            IoError::new(
+                ErrorKind::InvalidInput,
+                "image width exceeded length of provided scanline",
+            )
+        })?;
+
+        self.buffer.clear();
+
+        if self.run_length_encoding {
+            let width = self.width;
+            self.buffer
+                .extend_from_slice(&[2, 2, (width >> 8) as u8, width as u8]);
+
+            let pixels: Vec<[u8; 4]> = scanline.iter().map(|&rgb| to_rgbe(rgb)).collect();
+            let mut channel = Vec::with_capacity(width);
+            for c in 0..4 {
+                channel.clear();
+                channel.extend(pixels.iter().map(|pixel| pixel[c]));
+                encode_channel(&mut self.buffer, &channel);
+            }
+        } else {
+            for &rgb in scanline {
+                self.buffer.extend_from_slice(&to_rgbe(rgb));
+            }
+        }
+
+        self.writer.write_all(&self.buffer)
+    }
+
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Convert a pixel to RGBE, such that it decodes to the closest value [`crate::load`] can
+/// represent. Negative and NaN channels become zero.
+fn to_rgbe(Rgb { r, g, b }: Rgb) -> [u8; 4] {
+    let max = r.max(g).max(b);
+    if max.is_nan() || max <= 1e-32 {
+        return [0; 4];
+    }
+
+    // The exponent that puts max in the range [0.5, 1), like frexp. max is always a normal float.
+    let exponent = ((max.to_bits() >> 23) & 0xff) as i32 - 126;
+    let scale = 255. * 2_f32.powi(-exponent);
+    let mantissa = |c: f32| (c * scale + 0.5) as u8;
+
+    [
+        mantissa(r),
+        mantissa(g),
+        mantissa(b),
+        (exponent + 128).min(255) as u8,
+    ]
+}
+
+/// Run-length encode one channel of a scanline, using runs wherever at least four bytes repeat.
+fn encode_channel(out: &mut Vec<u8>, channel: &[u8]) {
+    const MIN_RUN: usize = 4;
+    const MAX_RUN: usize = 127;
+    const MAX_LITERAL: usize = 128;
+
+    let mut position = 0;
+    while position < channel.len() {
+        // Find where the next run that is worth encoding starts
+        let mut run_start = position;
+        let mut run_length = 0;
+        while run_start < channel.len() {
+            let value = channel[run_start];
+            run_length = channel[run_start..]
+                .iter()
+                .take(MAX_RUN)
+                .take_while(|&&byte| byte == value)
+                .count();
+
+            if run_length >= MIN_RUN {
+                break;
+            }
+            run_start += run_length;
+        }
+
+        for literal in channel[position..run_start].chunks(MAX_LITERAL) {
+            out.push(literal.len() as u8);
+            out.extend_from_slice(literal);
+        }
+
+        if run_start < channel.len() {
+            out.extend_from_slice(&[128 | run_length as u8, channel[run_start]]);
+            run_start += run_length;
+        }
+
+        position = run_start;
+    }
+}
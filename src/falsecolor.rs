@@ -0,0 +1,291 @@
+//! False-colour renderings of luminance, like Radiance's `falsecolor` tool. Requires the "std"
+//! feature.
+//!
+//! Pixel values are converted to luminance in cd/m² with the [`crate::LUMINOUS_EFFICACY`] of
+//! 179 lm/W, and then mapped onto a palette between zero and [`FalseColor::max`].
+//! ```rust
+//! use radiant::falsecolor::{FalseColor, Mode, Scale};
+//! use radiant::{Image, Rgb};
+//!
+//! let image = Image { width: 1, height: 1, data: vec![Rgb { r: 1.0, g: 1.0, b: 1.0 }] };
+//! let false_color = FalseColor {
+//!     max: 1000.0,
+//!     scale: Scale::Log { decades: 2.0 },
+//!     mode: Mode::Bands(8),
+//!     ..FalseColor::default()
+//! };
+//!
+//! let rendered = false_color.apply(&image);
+//! for entry in false_color.legend() {
+//!     println!("{} to {} cd/m²: {:?}", entry.min, entry.max, entry.colour);
+//! }
+//! ```
+
+use crate::{Image, Rgb};
+
+/// The colours that luminance is mapped onto, from the lowest to the highest value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Palette {
+    /// Dark blue through cyan, green and yellow to red, like Radiance's default palette.
+    Spectral,
+    /// Black through red, orange and yellow to white.
+    Heat,
+    /// Black to white.
+    Grayscale,
+    /// Evenly spaced colours to interpolate between, from [`Palette::custom`].
+    Custom(CustomPalette),
+}
+
+/// The colours of a [`Palette::Custom`], of which there are at least two.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomPalette {
+    stops: Vec<Rgb>,
+}
+
+impl CustomPalette {
+    /// The colours, from the lowest to the highest value.
+    pub fn stops(&self) -> &[Rgb] {
+        &self.stops
+    }
+}
+
+impl Palette {
+    /// A palette that interpolates between evenly spaced colours, from the lowest to the highest
+    /// value. Returns `None` if there are fewer than two colours.
+    pub fn custom(stops: Vec<Rgb>) -> Option<Self> {
+        if stops.len() < 2 {
+            return None;
+        }
+        Some(Self::Custom(CustomPalette { stops }))
+    }
+
+    /// The colour at `t`, which is clamped to be between 0 and 1.
+    pub fn colour(&self, t: f32) -> Rgb {
+        const fn rgb(r: f32, g: f32, b: f32) -> Rgb {
+            Rgb { r, g, b }
+        }
+
+        const SPECTRAL: [Rgb; 7] = [
+            rgb(0., 0., 0.5),
+            rgb(0., 0., 1.),
+            rgb(0., 1., 1.),
+            rgb(0., 1., 0.),
+            rgb(1., 1., 0.),
+            rgb(1., 0.5, 0.),
+            rgb(1., 0., 0.),
+        ];
+        const HEAT: [Rgb; 5] = [
+            rgb(0., 0., 0.),
+            rgb(1., 0., 0.),
+            rgb(1., 0.5, 0.),
+            rgb(1., 1., 0.),
+            rgb(1., 1., 1.),
+        ];
+        const GRAYSCALE: [Rgb; 2] = [rgb(0., 0., 0.), rgb(1., 1., 1.)];
+
+        let stops: &[Rgb] = match self {
+            Self::Spectral => &SPECTRAL,
+            Self::Heat => &HEAT,
+            Self::Grayscale => &GRAYSCALE,
+            Self::Custom(custom) => custom.stops(),
+        };
+
+        // Every palette has at least two stops
+        let last = stops.len() - 1;
+        let t = if t > 0. { t.min(1.) } else { 0. } * last as f32;
+        let i = (t as usize).min(last - 1);
+        stops[i].lerp(stops[i + 1], t - i as f32)
+    }
+}
+
+/// How luminance is mapped onto the palette.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scale {
+    /// From zero to the maximum.
+    Linear,
+    /// Logarithmic, covering the given number of decades below the maximum.
+    Log {
+        /// How many factors of ten the scale spans.
+        decades: f32,
+    },
+}
+
+/// Whether the scale is continuous, or divided into steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Every luminance gets its own colour.
+    Continuous,
+    /// The scale is divided into the given number of bands of solid colour. Zero bands are
+    /// treated as one.
+    Bands(usize),
+    /// Like [`Mode::Bands`], but only the edges between bands are drawn, on a black background.
+    Lines(usize),
+}
+
+/// A range of luminance on the legend of a [`FalseColor`] rendering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LegendEntry {
+    /// The lowest luminance in the range, in cd/m².
+    pub min: f32,
+    /// The highest luminance in the range, in cd/m².
+    pub max: f32,
+    /// The colour of the range. For [`Mode::Continuous`], this is the colour in its middle.
+    pub colour: Rgb,
+}
+
+/// The settings for a false-colour rendering.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FalseColor {
+    /// The colours to use.
+    pub palette: Palette,
+    /// How luminance is mapped onto the palette.
+    pub scale: Scale,
+    /// The luminance at the top of the scale, in cd/m². Anything brighter gets the last colour.
+    pub max: f32,
+    /// Whether the scale is continuous, or divided into bands or contour lines.
+    pub mode: Mode,
+}
+
+impl Default for FalseColor {
+    /// A continuous, linear [`Palette::Spectral`] scale up to 1000 cd/m², like Radiance's
+    /// `falsecolor`.
+    fn default() -> Self {
+        Self {
+            palette: Palette::Spectral,
+            scale: Scale::Linear,
+            max: 1000.,
+            mode: Mode::Continuous,
+        }
+    }
+}
+
+impl FalseColor {
+    /// Render the luminance of an image in false colour.
+    pub fn apply(&self, image: &Image) -> Image {
+        let positions: Vec<f32> = image
+            .data
+            .iter()
+            .map(|rgb| self.position(rgb.luminance()))
+            .collect();
+
+        let data = match self.mode {
+            Mode::Continuous => positions.iter().map(|&t| self.palette.colour(t)).collect(),
+            Mode::Bands(bands) => {
+                let bands = bands.max(1);
+                positions
+                    .iter()
+                    .map(|&t| self.band_colour(band(t, bands), bands))
+                    .collect()
+            }
+            Mode::Lines(bands) => {
+                let bands = bands.max(1);
+                let width = image.width;
+                let bands_at: Vec<usize> = positions.iter().map(|&t| band(t, bands)).collect();
+
+                // A pixel is on a contour if the band changes to its right or below it
+                (0..bands_at.len())
+                    .map(|i| {
+                        let b = bands_at[i];
+                        let right = (i % width + 1 < width).then(|| bands_at[i + 1]);
+                        let below = bands_at.get(i + width).copied();
+                        if right.is_some_and(|r| r != b) || below.is_some_and(|r| r != b) {
+                            self.band_colour(b, bands)
+                        } else {
+                            Rgb::zero()
+                        }
+                    })
+                    .collect()
+            }
+        };
+
+        Image {
+            width: image.width,
+            height: image.height,
+            data,
+        }
+    }
+
+    /// Render the luminance of an image in false colour, as 8-bit pixels in the same order as
+    /// [`Image::data`].
+    pub fn apply_rgb8(&self, image: &Image) -> Vec<[u8; 3]> {
+        self.apply(image)
+            .data
+            .iter()
+            .map(|rgb| [rgb.r, rgb.g, rgb.b].map(|c| (c.clamp(0., 1.) * 255. + 0.5) as u8))
+            .collect()
+    }
+
+    /// The luminance ranges and colours to show in a legend, from the lowest to the highest.
+    /// There is one entry per band, or eight for [`Mode::Continuous`].
+    pub fn legend(&self) -> Vec<LegendEntry> {
+        let (entries, banded) = match self.mode {
+            Mode::Continuous => (8, false),
+            Mode::Bands(bands) | Mode::Lines(bands) => (bands.max(1), true),
+        };
+
+        (0..entries)
+            .map(|i| LegendEntry {
+                min: self.luminance_at(i as f32 / entries as f32),
+                max: self.luminance_at((i + 1) as f32 / entries as f32),
+                colour: if banded {
+                    self.band_colour(i, entries)
+                } else {
+                    self.palette.colour((i as f32 + 0.5) / entries as f32)
+                },
+            })
+            .collect()
+    }
+
+    /// A vertical colour bar for the legend, with the top of the scale at the top.
+    pub fn legend_image(&self, width: usize, height: usize) -> Image {
+        let bands = match self.mode {
+            Mode::Continuous => None,
+            Mode::Bands(bands) | Mode::Lines(bands) => Some(bands.max(1)),
+        };
+
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let t = 1. - (y as f32 + 0.5) / height as f32;
+            let colour = match bands {
+                None => self.palette.colour(t),
+                Some(bands) => self.band_colour(band(t, bands), bands),
+            };
+            data.extend(std::iter::repeat_n(colour, width));
+        }
+
+        Image {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Where a luminance falls on the scale, between 0 and 1.
+    fn position(&self, luminance: f32) -> f32 {
+        let t = match self.scale {
+            Scale::Linear => luminance / self.max,
+            Scale::Log { decades } => 1. + (luminance / self.max).log10() / decades,
+        };
+        if t > 0. {
+            t.min(1.)
+        } else {
+            0.
+        }
+    }
+
+    /// The luminance at a position on the scale, the inverse of [`FalseColor::position`].
+    fn luminance_at(&self, t: f32) -> f32 {
+        match self.scale {
+            Scale::Linear => t * self.max,
+            Scale::Log { decades } => self.max * 10_f32.powf((t - 1.) * decades),
+        }
+    }
+
+    fn band_colour(&self, band: usize, bands: usize) -> Rgb {
+        self.palette.colour((band as f32 + 0.5) / bands as f32)
+    }
+}
+
+fn band(t: f32, bands: usize) -> usize {
+    ((t * bands as f32) as usize).min(bands.saturating_sub(1))
+}
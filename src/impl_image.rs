@@ -0,0 +1,135 @@
+//! Integration with the `image` crate, so that radiant can be used as its HDR codec.
+
+use crate::{Encoder, Image, Loader, Rgb};
+use image::error::{
+    ImageFormatHint, ParameterError, ParameterErrorKind, UnsupportedError, UnsupportedErrorKind,
+};
+use image::{
+    ColorType, ExtendedColorType, ImageDecoder, ImageEncoder, ImageError, ImageFormat, ImageResult,
+    Rgb32FImage,
+};
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
+
+const PIXEL_BYTES: usize = 3 * std::mem::size_of::<f32>();
+
+/// Decodes the image one scanline at a time, straight into the buffer provided by `image`.
+impl<R: BufRead> ImageDecoder for Loader<R> {
+    fn dimensions(&self) -> (u32, u32) {
+        let clamp = |length: usize| u32::try_from(length).unwrap_or(u32::MAX);
+        (clamp(self.width), clamp(self.height))
+    }
+
+    fn color_type(&self) -> ColorType {
+        ColorType::Rgb32F
+    }
+
+    fn read_image(self, buf: &mut [u8]) -> ImageResult<()> {
+        if self.width.checked_mul(self.height) != Some(buf.len() / PIXEL_BYTES) {
+            return Err(dimension_mismatch());
+        }
+
+        let mut scanlines = self.scanlines();
+        let mut scanline = vec![Rgb::zero(); scanlines.width];
+
+        for row in buf.chunks_exact_mut(scanlines.width.max(1) * PIXEL_BYTES) {
+            scanlines.read_scanline(&mut scanline)?;
+            for (bytes, rgb) in row.chunks_exact_mut(PIXEL_BYTES).zip(&scanline) {
+                let channels = [rgb.r, rgb.g, rgb.b].map(f32::to_ne_bytes);
+                bytes.copy_from_slice(channels.as_flattened());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_image_boxed(self: Box<Self>, buf: &mut [u8]) -> ImageResult<()> {
+        (*self).read_image(buf)
+    }
+}
+
+/// Encodes `Rgb32F` images, one scanline at a time.
+impl<W: Write> ImageEncoder for Encoder<W> {
+    fn write_image(
+        self,
+        buf: &[u8],
+        width: u32,
+        height: u32,
+        color_type: ExtendedColorType,
+    ) -> ImageResult<()> {
+        if color_type != ExtendedColorType::Rgb32F {
+            return Err(ImageError::Unsupported(
+                UnsupportedError::from_format_and_kind(
+                    ImageFormatHint::Exact(ImageFormat::Hdr),
+                    UnsupportedErrorKind::Color(color_type),
+                ),
+            ));
+        }
+
+        let (width, height) = (width as usize, height as usize);
+        if width.checked_mul(height) != Some(buf.len() / PIXEL_BYTES) {
+            return Err(dimension_mismatch());
+        }
+
+        let mut scanlines = self.scanlines(width, height)?;
+        let mut scanline = Vec::with_capacity(width);
+
+        for row in buf.chunks_exact(width.max(1) * PIXEL_BYTES) {
+            scanline.clear();
+            scanline.extend(row.chunks_exact(PIXEL_BYTES).map(|bytes| {
+                let channel = |i: usize| {
+                    let mut channel = [0; 4];
+                    channel.copy_from_slice(&bytes[i * 4..i * 4 + 4]);
+                    f32::from_ne_bytes(channel)
+                };
+                Rgb {
+                    r: channel(0),
+                    g: channel(1),
+                    b: channel(2),
+                }
+            }));
+            scanlines.write_scanline(&scanline)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Rgb32FImage> for Image {
+    fn from(image: Rgb32FImage) -> Self {
+        let (width, height) = image.dimensions();
+        let data = image
+            .pixels()
+            .map(|&image::Rgb([r, g, b])| Rgb { r, g, b })
+            .collect();
+
+        Self {
+            width: width as usize,
+            height: height as usize,
+            data,
+        }
+    }
+}
+
+/// Fails if the dimensions don't fit in a `u32`, or don't match the length of the data.
+impl TryFrom<Image> for Rgb32FImage {
+    type Error = ImageError;
+
+    fn try_from(image: Image) -> ImageResult<Self> {
+        let width = u32::try_from(image.width).map_err(|_| dimension_mismatch())?;
+        let height = u32::try_from(image.height).map_err(|_| dimension_mismatch())?;
+        let data = image
+            .data
+            .iter()
+            .flat_map(|rgb| [rgb.r, rgb.g, rgb.b])
+            .collect();
+
+        Self::from_raw(width, height, data).ok_or_else(dimension_mismatch)
+    }
+}
+
+fn dimension_mismatch() -> ImageError {
+    ImageError::Parameter(ParameterError::from_kind(
+        ParameterErrorKind::DimensionMismatch,
+    ))
+}
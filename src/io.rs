@@ -0,0 +1,121 @@
+//! The input and error types used by the loaders.
+//!
+//! With the "std" feature enabled (the default), these are simply re-exports from [`std::io`].
+//! Without it, this module provides minimal stand-ins that only need `core` and `alloc`, with
+//! [`BufRead`] implemented for byte slices.
+
+#[cfg(feature = "std")]
+pub use std::io::{BufRead, Error, ErrorKind};
+
+#[cfg(not(feature = "std"))]
+pub use self::no_std::*;
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    use core::fmt;
+
+    /// A list specifying general categories of errors, like [`std::io::ErrorKind`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ErrorKind {
+        /// The data was not valid for the operation.
+        InvalidData,
+        /// A parameter was incorrect.
+        InvalidInput,
+        /// The data ended before the operation could complete.
+        UnexpectedEof,
+        /// Any other error, such as one returned by a custom [`BufRead`] implementation.
+        Other,
+    }
+
+    impl ErrorKind {
+        fn as_str(self) -> &'static str {
+            match self {
+                Self::InvalidData => "invalid data",
+                Self::InvalidInput => "invalid input parameter",
+                Self::UnexpectedEof => "unexpected end of file",
+                Self::Other => "other error",
+            }
+        }
+    }
+
+    /// The error type returned by the loaders, like [`std::io::Error`].
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+        message: &'static str,
+    }
+
+    impl Error {
+        /// Create a new error of the given kind, with a description of what went wrong.
+        pub fn new(kind: ErrorKind, message: &'static str) -> Self {
+            Self { kind, message }
+        }
+
+        /// The general category of this error.
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Self {
+            Self::new(kind, kind.as_str())
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    /// A source of bytes with an internal buffer, like [`std::io::BufRead`].
+    pub trait BufRead {
+        /// Return the contents of the internal buffer, filling it with more data if it is empty.
+        /// An empty slice means that the end of the input has been reached.
+        fn fill_buf(&mut self) -> Result<&[u8], Error>;
+
+        /// Mark `amt` bytes of the buffer returned by [`BufRead::fill_buf`] as read.
+        fn consume(&mut self, amt: usize);
+
+        /// Read exactly enough bytes to fill `buf`, or fail with [`ErrorKind::UnexpectedEof`].
+        fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<(), Error> {
+            while !buf.is_empty() {
+                let available = self.fill_buf()?;
+                if available.is_empty() {
+                    return Err(ErrorKind::UnexpectedEof.into());
+                }
+
+                let count = available.len().min(buf.len());
+                buf[..count].copy_from_slice(&available[..count]);
+                self.consume(count);
+                buf = &mut buf[count..];
+            }
+            Ok(())
+        }
+    }
+
+    impl BufRead for &[u8] {
+        #[inline]
+        fn fill_buf(&mut self) -> Result<&[u8], Error> {
+            Ok(self)
+        }
+
+        #[inline]
+        fn consume(&mut self, amt: usize) {
+            *self = &self[amt..];
+        }
+    }
+
+    impl<R: BufRead + ?Sized> BufRead for &mut R {
+        #[inline]
+        fn fill_buf(&mut self) -> Result<&[u8], Error> {
+            (**self).fill_buf()
+        }
+
+        #[inline]
+        fn consume(&mut self, amt: usize) {
+            (**self).consume(amt)
+        }
+    }
+}
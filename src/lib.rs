@@ -1,4 +1,5 @@
 #![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 //! # Radiant
 //!
@@ -37,14 +38,87 @@
 //! [Simple HDR Viewer application](https://github.com/iwikal/radiant/blob/master/examples/view_hdr.rs)
 //!
 //! Huge thanks to [HDRI Haven](https://hdrihaven.com) for providing CC0 sample images for testing!
+//!
+//! ## `no_std`
+//!
+//! The "std" feature is enabled by default. Without it, the crate only depends on `core` and
+//! `alloc`, and the loaders read from the minimal `radiant::io::BufRead` trait instead, which is
+//! implemented for `&[u8]`. Functionality that needs floating point math from `std`, like
+//! reprojecting environment maps, is not available.
+//!
+//! ## Crate layout
+//!
+//! The crate root holds what it takes to read and write Radiance HDR files: `Image`, `Rgb`, the
+//! loaders, `Encoder` and `Header`. Every other feature is a public module named after it, such
+//! as `pfm`, `compare`, `falsecolor` or `blur`, and its free functions and types are used through
+//! that module, e.g. `radiant::pfm::load` or `radiant::blur::Bloom`. Methods that a module adds to
+//! `Image`, like `Image::gaussian_blur` or `Image::crop`, are available wherever `Image` is, so
+//! modules that only add methods are private.
+//!
+//! ## The `image` crate
+//!
+//! The "impl-image" feature integrates with the [image crate](https://crates.io/crates/image):
+//! `Loader` implements `image::ImageDecoder`, `Encoder` implements `image::ImageEncoder`, and
+//! `Image` converts to and from `image::Rgb32FImage`. Decoding and encoding still happen one
+//! scanline at a time.
+//!
+//! ## Command line tool
+//!
+//! The "cli" feature builds a `radiant` binary, with `info`, `validate` and `convert`
+//! subcommands for inspecting, checking and transcoding images:
+//! ```sh
+//! cargo install radiant --features cli
+//! radiant convert image.hdr preview.ppm --exposure -1 --curve aces
+//! ```
 
 // Original source: http://flipcode.com/archives/HDR_Image_Reader.shtml
-use std::io::{BufRead, Error as IoError, ErrorKind};
+extern crate alloc;
+
+use alloc::vec::Vec;
+use io::{BufRead, Error as IoError, ErrorKind};
 
+mod arithmetic;
+#[cfg(feature = "std")]
+pub mod blur;
+#[cfg(feature = "std")]
+pub mod bracketing;
+#[cfg(feature = "std")]
+pub mod compare;
+mod convert;
+#[cfg(feature = "std")]
+mod encoder;
+#[cfg(feature = "std")]
+pub mod falsecolor;
+#[cfg(feature = "impl-image")]
+mod impl_image;
+pub mod io;
 mod loader;
+#[cfg(feature = "std")]
+pub mod local_tonemap;
+#[cfg(feature = "std")]
+pub mod lut;
+#[cfg(feature = "std")]
+pub mod pfm;
+#[cfg(feature = "std")]
+mod photometry;
+#[cfg(feature = "std")]
+mod ppm;
+#[cfg(feature = "std")]
+pub mod projection;
+#[cfg(feature = "std")]
+pub mod tonemap;
+mod transform;
+#[cfg(feature = "std")]
+pub mod view;
 
+#[cfg(feature = "std")]
+pub use encoder::*;
 pub use loader::*;
 
+/// The luminous efficacy that Radiance assumes for white light, in lumens per watt. It converts
+/// radiance in W/sr/m² to luminance in cd/m².
+pub const LUMINOUS_EFFICACY: f32 = 179.;
+
 /// The decoded R, G, and B value of a pixel. You typically get these from the data field on an
 /// [`Image`].
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -75,16 +149,50 @@ impl Rgb {
 
     #[inline]
     fn apply_exposure(&mut self, expo: u8) {
-        let expo = i32::from(expo) - 128;
-        let d = 2_f32.powi(expo) / 255_f32;
+        let d = exposure_scale(expo) / 255_f32;
 
         self.r *= d;
         self.g *= d;
         self.b *= d;
     }
+
+    /// The luminance of the pixel in cd/m², treating it as radiance in W/sr/m² the way Radiance
+    /// does: `179 * (0.265 * r + 0.670 * g + 0.065 * b)`.
+    #[inline]
+    pub fn luminance(self) -> f32 {
+        LUMINOUS_EFFICACY * (0.265 * self.r + 0.670 * self.g + 0.065 * self.b)
+    }
+
+    /// The luminance of the pixel in the same units as its channels, so that white has a
+    /// luminance of 1.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn relative_luminance(self) -> f32 {
+        self.luminance() / LUMINOUS_EFFICACY
+    }
+
+    /// Interpolate linearly towards another pixel, where `t` is 0 for this pixel and 1 for the
+    /// other one.
+    #[inline]
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Calculate 2 to the power of `expo - 128`, without relying on `std` for `powi`. An exponent of
+/// zero always means black, so it gives zero rather than the smallest subnormal.
+#[inline]
+const fn exposure_scale(expo: u8) -> f32 {
+    match expo {
+        0 => 0.,
+        // 2^-127 is too small for a normal float
+        1 => f32::from_bits(1 << 22),
+        // The biased float exponent of 2^(expo - 128) is expo - 128 + 127
+        _ => f32::from_bits(((expo - 1) as u32) << 23),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 struct Rgbe {
     r: u8,
     g: u8,
@@ -92,7 +200,7 @@ struct Rgbe {
     e: u8,
 }
 
-impl std::convert::From<Rgbe> for Rgb {
+impl From<Rgbe> for Rgb {
     #[inline]
     fn from(rgbe: Rgbe) -> Self {
         let mut rgb = Self {
@@ -105,14 +213,14 @@ impl std::convert::From<Rgbe> for Rgb {
     }
 }
 
-impl std::convert::From<[u8; 4]> for Rgbe {
+impl From<[u8; 4]> for Rgbe {
     #[inline]
     fn from([r, g, b, e]: [u8; 4]) -> Self {
         Self { r, g, b, e }
     }
 }
 
-impl std::convert::From<Rgbe> for [u8; 4] {
+impl From<Rgbe> for [u8; 4] {
     #[inline]
     fn from(Rgbe { r, g, b, e }: Rgbe) -> Self {
         [r, g, b, e]
@@ -168,20 +276,20 @@ impl From<LoadError> for IoError {
 type LoadResult<T = ()> = Result<T, LoadError>;
 
 trait ReadExt {
-    fn read_byte(&mut self) -> std::io::Result<u8>;
-    fn read_rgbe(&mut self) -> std::io::Result<Rgbe>;
+    fn read_byte(&mut self) -> Result<u8, IoError>;
+    fn read_rgbe(&mut self) -> Result<Rgbe, IoError>;
 }
 
 impl<R: BufRead> ReadExt for R {
     #[inline]
-    fn read_byte(&mut self) -> std::io::Result<u8> {
+    fn read_byte(&mut self) -> Result<u8, IoError> {
         let mut buf = [0u8];
         self.read_exact(&mut buf)?;
         Ok(buf[0])
     }
 
     #[inline]
-    fn read_rgbe(&mut self) -> std::io::Result<Rgbe> {
+    fn read_rgbe(&mut self) -> Result<Rgbe, IoError> {
         let mut buf = [0u8; 4];
         self.read_exact(&mut buf)?;
         Ok(buf.into())
@@ -189,7 +297,7 @@ impl<R: BufRead> ReadExt for R {
 }
 
 /// A decoded Radiance HDR image.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Image {
     /// The width of the image, in pixels.
     pub width: usize,
@@ -216,3 +324,22 @@ impl Image {
 pub fn load<R: BufRead>(reader: R) -> Result<Image, IoError> {
     Loader::new(reader)?.load_image()
 }
+
+/// Load a Radiance HDR image that is already in memory. This produces the same result as [`load`],
+/// but decodes straight out of the slice instead of going through a reader, which is faster.
+pub fn load_from_slice(bytes: &[u8]) -> Result<Image, IoError> {
+    loader::load_slice(bytes)
+}
+
+/// Load a Radiance HDR image from a file by memory-mapping it, and decoding it with
+/// [`load_from_slice`]. Requires the "memmap" feature.
+///
+/// The file must not be modified or truncated by another process while it is being loaded. See
+/// [`memmap2::Mmap`] for details.
+#[cfg(feature = "memmap")]
+pub fn load_mmap<P: AsRef<std::path::Path>>(path: P) -> Result<Image, IoError> {
+    let file = std::fs::File::open(path)?;
+    // Safety: see the documentation above
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+    load_from_slice(&map)
+}
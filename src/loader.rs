@@ -1,7 +1,25 @@
-use crate::{Image, LoadError, LoadResult, ReadExt, Rgb};
-use std::io::{BufRead, Error as IoError, ErrorKind};
+use crate::io::{BufRead, Error as IoError, ErrorKind};
+use crate::{convert, Image, LoadError, LoadResult, ReadExt, Rgb, Rgbe};
+use alloc::{vec, vec::Vec};
 
+#[cfg(feature = "async")]
+mod async_loader;
+mod decoder;
 mod header;
+mod incremental;
+mod lenient;
+#[cfg(feature = "std")]
+mod random_access;
+mod slice;
+
+#[cfg(feature = "async")]
+pub use async_loader::*;
+pub use decoder::*;
+pub use header::Header;
+pub use lenient::*;
+#[cfg(feature = "std")]
+pub use random_access::*;
+pub(crate) use slice::load_slice;
 
 const MAGIC: &[u8; 10] = b"#?RADIANCE";
 
@@ -11,25 +29,32 @@ pub struct Loader<R> {
     pub width: usize,
     /// The height of the image, in pixels.
     pub height: usize,
+    /// The variables and other lines in the header.
+    pub header: Header,
     reader: R,
 }
 
 impl<R: BufRead> Loader<R> {
     /// Construct a new [`Loader`]. This will consume the header from the provided reader.
-    pub fn new(mut reader: R) -> Result<Self, IoError> {
+    pub fn new(reader: R) -> Result<Self, IoError> {
+        Ok(Self::parse(reader)?)
+    }
+
+    fn parse(mut reader: R) -> LoadResult<Self> {
         let mut buf = [0u8; MAGIC.len()];
-        reader.read_exact(&mut buf).map_err(LoadError::from)?;
+        reader.read_exact(&mut buf)?;
 
         if &buf != MAGIC {
-            return Err(LoadError::FileFormat.into());
+            return Err(LoadError::FileFormat);
         }
 
         // Grab image dimensions
-        let (width, height, reader) = header::parse_header(reader)?;
+        let (width, height, header, reader) = header::parse_header(reader)?;
 
         Ok(Self {
             width,
             height,
+            header,
             reader,
         })
     }
@@ -40,6 +65,7 @@ impl<R: BufRead> Loader<R> {
             width: self.width,
             height: self.height,
             reader: self.reader,
+            planes: Vec::new(),
         }
     }
 
@@ -65,6 +91,104 @@ impl<R: BufRead> Loader<R> {
             data,
         })
     }
+
+    /// Load only a rectangular region of the image, starting at the given x and y coordinate.
+    /// Scanlines above the region are skipped over without being decoded, and decoding stops once
+    /// the last row of the region has been read. If the region does not fit within the image, an
+    /// error of the kind [`std::io::ErrorKind::InvalidInput`] will be returned.
+    pub fn load_region(
+        self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<Image, IoError> {
+        let fits = |start: usize, length: usize, limit: usize| matches!(start.checked_add(length), Some(end) if end <= limit);
+        if !fits(x, width, self.width) || !fits(y, height, self.height) {
+            return Err(invalid_region());
+        }
+
+        let length = width.checked_mul(height).ok_or(LoadError::Header)?;
+        let mut data = vec![Rgb::zero(); length];
+
+        if height != 0 {
+            let mut scanlines = self.scanlines();
+
+            for _ in 0..y {
+                scanlines.skip_scanline()?;
+            }
+
+            for row in data.chunks_mut(width.max(1)) {
+                scanlines.read_scanline_columns(x, row)?;
+            }
+        }
+
+        Ok(Image {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Load a downscaled copy of the image, where each pixel is the average of a `factor` by
+    /// `factor` block of the original. The image is box-filtered one scanline at a time while it
+    /// is being decoded, so the full-resolution image is never held in memory. If the dimensions
+    /// are not a multiple of `factor`, the blocks along the right and bottom edges are averaged
+    /// over the pixels they do cover. A factor of zero results in an error of the kind
+    /// [`std::io::ErrorKind::InvalidInput`].
+    pub fn load_scaled(self, factor: usize) -> Result<Image, IoError> {
+        if factor == 0 {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "the scale factor must not be zero",
+            ));
+        }
+
+        let &Self { width, height, .. } = &self;
+        let scaled_width = width.div_ceil(factor);
+        let scaled_height = height.div_ceil(factor);
+        let length = scaled_width
+            .checked_mul(scaled_height)
+            .ok_or(LoadError::Header)?;
+
+        let mut data = vec![Rgb::zero(); length];
+
+        if length != 0 {
+            let mut scanlines = self.scanlines();
+            let mut scanline = vec![Rgb::zero(); width];
+
+            for y in 0..height {
+                scanlines.read_scanline(&mut scanline)?;
+
+                let start = y / factor * scaled_width;
+                let row = &mut data[start..start + scaled_width];
+                for (sum, block) in row.iter_mut().zip(scanline.chunks(factor)) {
+                    for pixel in block {
+                        sum.r += pixel.r;
+                        sum.g += pixel.g;
+                        sum.b += pixel.b;
+                    }
+                }
+            }
+
+            for (y, row) in data.chunks_mut(scaled_width).enumerate() {
+                let block_height = factor.min(height - y * factor);
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    let block_width = factor.min(width - x * factor);
+                    let scale = 1. / (block_width * block_height) as f32;
+                    pixel.r *= scale;
+                    pixel.g *= scale;
+                    pixel.b *= scale;
+                }
+            }
+        }
+
+        Ok(Image {
+            width: scaled_width,
+            height: scaled_height,
+            data,
+        })
+    }
 }
 
 /// An image loader that decodes images line by line, through an iterative API.
@@ -100,6 +224,8 @@ pub struct ScanlinesLoader<R> {
     /// The height of the image, i.e. the number of scanlines.
     pub height: usize,
     reader: R,
+    /// Scratch space for decoding the channels of a scanline separately.
+    planes: Vec<u8>,
 }
 
 impl<R: BufRead> ScanlinesLoader<R> {
@@ -111,20 +237,27 @@ impl<R: BufRead> ScanlinesLoader<R> {
             .get_mut(..self.width)
             .ok_or_else(Self::invalid_input)?;
 
-        if !scanline.is_empty() {
-            const MIN_LEN: usize = 8;
-            const MAX_LEN: usize = 0x7fff;
-
-            let rgbe = self.reader.read_rgbe()?;
+        self.decode_scanline(0, scanline)?;
+        Ok(())
+    }
 
-            if (MIN_LEN..=MAX_LEN).contains(&scanline.len()) && rgbe.is_new_decrunch_marker() {
-                self.new_decrunch(scanline)?;
-            } else {
-                scanline[0] = rgbe.into();
-                self.old_decrunch(scanline)?;
-            }
+    /// Decode the next horizontal scanline of the image, but only keep the pixels from column `x`
+    /// onwards, filling the provided buffer. Pixels outside of that range are never converted. If
+    /// the columns extend past the width of the image, an error of the kind
+    /// [`std::io::ErrorKind::InvalidInput`] will be returned.
+    pub fn read_scanline_columns(&mut self, x: usize, columns: &mut [Rgb]) -> Result<(), IoError> {
+        match x.checked_add(columns.len()) {
+            Some(end) if end <= self.width => {}
+            _ => return Err(invalid_region()),
         }
 
+        self.decode_scanline(x, columns)?;
+        Ok(())
+    }
+
+    /// Skip over the next horizontal scanline of the image without decoding any of it.
+    pub fn skip_scanline(&mut self) -> Result<(), IoError> {
+        self.decode_scanline(0, &mut [])?;
         Ok(())
     }
 
@@ -135,29 +268,50 @@ impl<R: BufRead> ScanlinesLoader<R> {
         )
     }
 
-    fn old_decrunch(&mut self, mut scanline: &mut [Rgb]) -> LoadResult {
+    /// Decode one scanline, writing only the columns covered by `window`, which starts at column
+    /// `start`.
+    fn decode_scanline(&mut self, start: usize, window: &mut [Rgb]) -> LoadResult {
+        let mut window = Window { start, window };
+
+        if self.width != 0 {
+            const MIN_LEN: usize = 8;
+            const MAX_LEN: usize = 0x7fff;
+
+            let rgbe = self.reader.read_rgbe()?;
+
+            if (MIN_LEN..=MAX_LEN).contains(&self.width) && rgbe.is_new_decrunch_marker() {
+                self.new_decrunch(&mut window)?;
+            } else {
+                self.old_decrunch(&mut window, rgbe)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn old_decrunch(&mut self, window: &mut Window, first: Rgbe) -> LoadResult {
+        window.fill(0, 1, first);
+
+        let mut previous = first;
+        let mut position = 1;
         let mut l_shift = 0;
 
-        while scanline.len() > 1 {
+        while position < self.width {
             let rgbe = self.reader.read_rgbe()?;
             if rgbe.is_rle_marker() {
                 let count = usize::checked_shl(1, l_shift)
                     .and_then(|shift_factor| usize::from(rgbe.e).checked_mul(shift_factor))
+                    .filter(|&count| count <= self.width - position)
                     .ok_or(LoadError::Rle)?;
 
-                let from = scanline[0];
-
-                scanline
-                    .get_mut(1..=count)
-                    .ok_or(LoadError::Rle)?
-                    .iter_mut()
-                    .for_each(|to| *to = from);
+                window.fill(position, count, previous);
 
-                scanline = &mut scanline[count..];
+                position += count;
                 l_shift += 8;
             } else {
-                scanline[1] = rgbe.into();
-                scanline = &mut scanline[1..];
+                window.fill(position, 1, rgbe);
+                previous = rgbe;
+                position += 1;
                 l_shift = 0;
             }
         }
@@ -165,58 +319,120 @@ impl<R: BufRead> ScanlinesLoader<R> {
         Ok(())
     }
 
-    fn new_decrunch(&mut self, scanline: &mut [Rgb]) -> LoadResult {
-        let mut decrunch_channel = |mutate_pixel: fn(&mut Rgb, u8)| -> LoadResult<()> {
-            let mut scanline = &mut *scanline;
-            while !scanline.is_empty() {
-                let code = self.reader.read_byte()? as usize;
-                if code > 128 {
-                    // run
-                    let val = self.reader.read_byte()?;
+    fn new_decrunch(&mut self, window: &mut Window) -> LoadResult {
+        let width = self.width;
+        let (start, len) = (window.start, window.window.len());
+        let end = start + len;
+        self.planes.resize(len * 4, 0);
+        let reader = &mut self.reader;
+
+        // Each channel is stored separately, so decode them into planes first. The planes only
+        // hold the columns of the window, and the data for the other columns is skipped.
+        for channel in 0..4 {
+            let plane = &mut self.planes[channel * len..(channel + 1) * len];
+            let mut position = 0;
+            while position < width {
+                let code = reader.read_byte()? as usize;
+                let (count, run) = if code > 128 {
+                    (code & 127, true)
+                } else {
+                    (code, false)
+                };
+                if count > width - position {
+                    return Err(LoadError::Rle);
+                }
 
-                    let count = code & 127;
-                    scanline
-                        .get_mut(..count)
-                        .ok_or(LoadError::Rle)?
-                        .iter_mut()
-                        .for_each(|pixel| mutate_pixel(pixel, val));
+                // The columns of this run or copy that fall inside the window
+                let from = position.clamp(start, end);
+                let to = (position + count).clamp(start, end);
+                let kept = &mut plane[from - start..to - start];
 
-                    scanline = &mut scanline[count..];
+                if run {
+                    // run
+                    let val = reader.read_byte()?;
+                    kept.fill(val);
+                } else if kept.is_empty() {
+                    // non-run, outside of the window
+                    skip(reader, count)?;
                 } else {
                     // non-run
-                    let mut bytes_left = code;
-                    while bytes_left > 0 {
-                        let buf = self.reader.fill_buf()?;
-
-                        if buf.is_empty() {
-                            return Err(LoadError::Eof);
-                        }
-
-                        let count = buf.len().min(bytes_left);
-                        scanline
-                            .get_mut(..count)
-                            .ok_or(LoadError::Rle)?
-                            .iter_mut()
-                            .zip(buf)
-                            .for_each(|(pixel, &val)| mutate_pixel(pixel, val));
-
-                        scanline = &mut scanline[count..];
-                        self.reader.consume(count);
-                        bytes_left -= count;
-                    }
+                    skip(reader, from - position)?;
+                    reader.read_exact(kept)?;
+                    skip(reader, position + count - to)?;
                 }
+
+                position += count;
             }
+        }
+
+        window.convert_planes(&self.planes);
+        Ok(())
+    }
+}
+
+/// Skip over `count` bytes of image data without looking at them.
+fn skip<R: BufRead>(reader: &mut R, mut count: usize) -> LoadResult {
+    while count > 0 {
+        let available = reader.fill_buf()?.len().min(count);
+        if available == 0 {
+            return Err(LoadError::Eof);
+        }
+        reader.consume(available);
+        count -= available;
+    }
+    Ok(())
+}
+
+/// The columns of a scanline that are actually being kept, starting at column `start`.
+struct Window<'a> {
+    start: usize,
+    window: &'a mut [Rgb],
+}
+
+impl Window<'_> {
+    /// The pixels of the window that overlap the `count` columns starting at `position`.
+    #[inline]
+    fn get_mut(&mut self, position: usize, count: usize) -> &mut [Rgb] {
+        let end = self.start + self.window.len();
+        let from = position.clamp(self.start, end);
+        let to = (position + count).clamp(self.start, end);
+        &mut self.window[from - self.start..to - self.start]
+    }
 
-            Ok(())
-        };
+    /// Convert the window from planar RGBE data that only holds the columns of the window.
+    fn convert_planes(&mut self, planes: &[u8]) {
+        let mut channels = planes.chunks_exact(self.window.len().max(1));
+        let mut channel = || channels.next().unwrap_or_default();
+        let planes = [channel(), channel(), channel(), channel()];
+        convert::rgbe_to_rgb(planes, self.window);
+    }
 
-        decrunch_channel(|pixel, val| pixel.r = val as f32)?;
-        decrunch_channel(|pixel, val| pixel.g = val as f32)?;
-        decrunch_channel(|pixel, val| pixel.b = val as f32)?;
-        decrunch_channel(Rgb::apply_exposure)
+    #[inline]
+    fn fill(&mut self, position: usize, count: usize, rgbe: Rgbe) {
+        let pixels = self.get_mut(position, count);
+        if !pixels.is_empty() {
+            let pixel = rgbe.into();
+            pixels.iter_mut().for_each(|to| *to = pixel);
+        }
     }
 }
 
+/// Parse a header from the start of a buffer, returning the dimensions of the image and the
+/// length of the header in bytes.
+fn parse_buffered_header(mut bytes: &[u8]) -> LoadResult<(usize, usize, usize)> {
+    let len = bytes.len();
+    let loader = Loader::parse(&mut bytes)?;
+    Ok((loader.width, loader.height, len - bytes.len()))
+}
+
+fn invalid_region() -> IoError {
+    IoError::new(
+        ErrorKind::InvalidInput,
+        "the requested region exceeded the dimensions of the image",
+    )
+}
+
+#[allow(dead_code)]
 struct ScanlinesIter<R> {
     loader: ScanlinesLoader<R>,
 }
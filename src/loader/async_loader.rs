@@ -0,0 +1,118 @@
+use super::incremental::{HeaderState, ScanlineState};
+use super::ScanlinesLoader;
+use crate::{Image, LoadError, LoadResult, Rgb};
+use futures_util::io::{AsyncBufRead, AsyncBufReadExt};
+use std::io::Error as IoError;
+
+/// The asynchronous counterpart of [`crate::Loader`], which reads from an [`AsyncBufRead`] instead of a
+/// [`std::io::BufRead`]. Requires the "async" feature.
+///
+/// Data is decoded straight from the buffer of the underlying reader. When a header or scanline
+/// continues past the end of that buffer, decoding picks up where it left off once more data has
+/// arrived, so every byte is only decoded once.
+pub struct AsyncLoader<R> {
+    /// The width of the image, in pixels.
+    pub width: usize,
+    /// The height of the image, in pixels.
+    pub height: usize,
+    reader: R,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncLoader<R> {
+    /// Construct a new [`AsyncLoader`]. This will consume the header from the provided reader.
+    pub async fn new(mut reader: R) -> Result<Self, IoError> {
+        let mut header = HeaderState::default();
+        let (width, height) = parse(&mut reader, |bytes| header.feed(bytes)).await?;
+
+        Ok(Self {
+            width,
+            height,
+            reader,
+        })
+    }
+
+    /// Convert this loader into an [`AsyncScanlinesLoader`], which lets you load the image one
+    /// scanline at a time.
+    pub fn scanlines(self) -> AsyncScanlinesLoader<R> {
+        AsyncScanlinesLoader {
+            width: self.width,
+            height: self.height,
+            reader: self.reader,
+            state: ScanlineState::new(self.width),
+        }
+    }
+
+    /// Load an entire [`Image`] at once.
+    pub async fn load_image(self) -> Result<Image, IoError> {
+        let &Self { width, height, .. } = &self;
+        let length = width.checked_mul(height).ok_or(LoadError::Header)?;
+
+        let mut data = vec![Rgb::zero(); length];
+
+        if length != 0 {
+            let mut scanlines = self.scanlines();
+
+            for y in 0..height {
+                let start = y * width;
+                scanlines.read_scanline(&mut data[start..]).await?;
+            }
+        }
+
+        Ok(Image {
+            width,
+            height,
+            data,
+        })
+    }
+}
+
+/// The asynchronous counterpart of [`ScanlinesLoader`]. Requires the "async" feature.
+pub struct AsyncScanlinesLoader<R> {
+    /// The width of the image.
+    pub width: usize,
+    /// The height of the image, i.e. the number of scanlines.
+    pub height: usize,
+    reader: R,
+    state: ScanlineState,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncScanlinesLoader<R> {
+    /// Decode image data into the next horizontal scanline of the image. The provided scanline
+    /// buffer must be at least as long as the width of the image, otherwise an error of the kind
+    /// [`std::io::ErrorKind::InvalidInput`] will be returned.
+    pub async fn read_scanline(&mut self, scanline: &mut [Rgb]) -> Result<(), IoError> {
+        let width = self.width;
+        let scanline = scanline
+            .get_mut(..width)
+            .ok_or_else(ScanlinesLoader::<&[u8]>::invalid_input)?;
+
+        let state = &mut self.state;
+        parse(&mut self.reader, |bytes| {
+            let (used, done) = state.decode(bytes, scanline)?;
+            Ok((used, done.then_some(())))
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Run a resumable parser over the data of a reader, one buffer at a time. The parser returns the
+/// number of bytes it used, and its result once it is done.
+async fn parse<R: AsyncBufRead + Unpin, T>(
+    reader: &mut R,
+    mut parse: impl FnMut(&[u8]) -> LoadResult<(usize, Option<T>)>,
+) -> LoadResult<T> {
+    loop {
+        let bytes = reader.fill_buf().await?;
+        if bytes.is_empty() {
+            return Err(LoadError::Eof);
+        }
+
+        let (used, parsed) = parse(bytes)?;
+        reader.consume_unpin(used);
+        if let Some(value) = parsed {
+            return Ok(value);
+        }
+    }
+}
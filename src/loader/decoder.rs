@@ -0,0 +1,154 @@
+use super::incremental::{HeaderState, ScanlineState};
+use crate::io::Error as IoError;
+use crate::Rgb;
+use alloc::{vec, vec::Vec};
+
+/// A push-based decoder, for when the image data arrives in pieces and there is no reader to pull
+/// it from, such as when streaming over a network. Feed it data as it becomes available, and call
+/// [`Decoder::decode`] to find out what could be decoded from it so far.
+/// ```rust
+/// use radiant::{Decoded, Decoder};
+///
+/// let file = b"#?RADIANCE\n\n-Y 1 +X 2\n\xff\x00\xff\x80\x01\x01\x01\x01";
+/// let mut decoder = Decoder::new();
+///
+/// for chunk in file.chunks(3) {
+///     decoder.feed(chunk);
+///     loop {
+///         match decoder.decode().expect("failed to decode image") {
+///             Decoded::NeedMoreData => break,
+///             Decoded::Header { width, height } => println!("{}x{}", width, height),
+///             Decoded::Scanline { y, scanline } => println!("row {}: {:?}", y, scanline),
+///             Decoded::Done => return,
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Decoder {
+    input: Input,
+    state: State,
+}
+
+#[derive(Debug, Default)]
+struct Input {
+    bytes: Vec<u8>,
+    /// The position of the first byte that hasn't been decoded yet.
+    offset: usize,
+}
+
+impl Input {
+    fn unread(&self) -> &[u8] {
+        &self.bytes[self.offset..]
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        // Only move the unread data to the front once it is the smaller part of the buffer, so
+        // that every byte is moved at most a few times
+        if self.offset > 0 && self.offset >= self.bytes.len() / 2 {
+            self.bytes.drain(..self.offset);
+            self.offset = 0;
+        }
+        self.bytes.extend_from_slice(bytes);
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Header(HeaderState),
+    Scanlines {
+        height: usize,
+        y: usize,
+        scanline: Vec<Rgb>,
+        decoder: ScanlineState,
+    },
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::Header(HeaderState::default())
+    }
+}
+
+/// The progress reported by [`Decoder::decode`].
+#[derive(Debug, PartialEq)]
+pub enum Decoded<'a> {
+    /// Nothing more can be decoded until more data is fed to the decoder.
+    NeedMoreData,
+    /// The header is complete, and the image has the given dimensions.
+    Header {
+        /// The width of the image, in pixels.
+        width: usize,
+        /// The height of the image, i.e. the number of scanlines.
+        height: usize,
+    },
+    /// The scanline at row `y` has been decoded.
+    Scanline {
+        /// The row of the scanline, counting from the top of the image.
+        y: usize,
+        /// The decoded pixels of the scanline.
+        scanline: &'a [Rgb],
+    },
+    /// Every scanline of the image has been decoded. Any data fed after this point is ignored.
+    Done,
+}
+
+impl Decoder {
+    /// Construct a new [`Decoder`] that expects the start of a file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a piece of data to the decoder's buffer. Nothing is decoded until
+    /// [`Decoder::decode`] is called.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.input.push(bytes);
+    }
+
+    /// Decode as far as the next header or scanline, if enough data has been fed to do so. The
+    /// decoder remembers how far it got within a header or scanline, so it is fine for a chunk to
+    /// end anywhere, and every byte is only decoded once.
+    pub fn decode(&mut self) -> Result<Decoded<'_>, IoError> {
+        let Self { input, state } = self;
+
+        match state {
+            State::Scanlines { height, y, .. } if y == height => Ok(Decoded::Done),
+            State::Header(header) => {
+                let (used, dimensions) = header.feed(input.unread())?;
+                input.offset += used;
+
+                match dimensions {
+                    Some((width, height)) => {
+                        *state = State::Scanlines {
+                            height,
+                            y: 0,
+                            scanline: vec![Rgb::zero(); width],
+                            decoder: ScanlineState::new(width),
+                        };
+                        Ok(Decoded::Header { width, height })
+                    }
+                    None => Ok(Decoded::NeedMoreData),
+                }
+            }
+            State::Scanlines {
+                y,
+                scanline,
+                decoder,
+                ..
+            } => {
+                let (used, done) = decoder.decode(input.unread(), scanline)?;
+                input.offset += used;
+
+                if done {
+                    *y += 1;
+                    Ok(Decoded::Scanline {
+                        y: *y - 1,
+                        scanline,
+                    })
+                } else {
+                    Ok(Decoded::NeedMoreData)
+                }
+            }
+        }
+    }
+}
@@ -1,18 +1,103 @@
 use super::{LoadError, LoadResult, ReadExt};
-use std::io::BufRead;
+use crate::io::BufRead;
+use alloc::string::String;
+use alloc::vec::Vec;
 
-const EOL: u8 = 0xA;
+pub(super) const EOL: u8 = 0xA;
 
-pub(crate) fn parse_header<R: BufRead>(mut reader: R) -> LoadResult<(usize, usize, R)> {
-    // Skip first paragraph
+/// The information in the header of a Radiance HDR file, apart from its dimensions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Header {
+    /// The `NAME=value` variables, in the order they appear in the file.
+    pub variables: Vec<(String, String)>,
+    /// Every other line, such as comments and the commands that produced the file.
+    pub other: Vec<String>,
+}
+
+impl Header {
+    /// The value of the last variable with the given name, if there is one.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The pixel format given by the `FORMAT` variable, such as `32-bit_rle_rgbe`.
+    pub fn format(&self) -> Option<&str> {
+        self.get("FORMAT")
+    }
+
+    /// The ratio of the height of a pixel to its width, given by the `PIXASPECT` variable. Like
+    /// Radiance, this multiplies every occurrence together, and defaults to 1 for square pixels.
+    /// Values that aren't positive numbers are ignored.
+    pub fn pixel_aspect(&self) -> f32 {
+        self.variables
+            .iter()
+            .filter(|(name, _)| name == "PIXASPECT")
+            .filter_map(|(_, value)| value.parse::<f32>().ok())
+            .filter(|&aspect| aspect > 0. && aspect.is_finite())
+            .product()
+    }
+
+    /// The factor that the pixels have been multiplied by since they were rendered, given by the
+    /// `EXPOSURE` variable. Like [`Header::pixel_aspect`], this multiplies every occurrence
+    /// together, defaults to 1, and ignores values that aren't positive numbers. Divide by it to
+    /// get back the original radiance, e.g. before measuring luminance.
+    pub fn exposure(&self) -> f32 {
+        self.variables
+            .iter()
+            .filter(|(name, _)| name == "EXPOSURE")
+            .filter_map(|(_, value)| value.parse::<f32>().ok())
+            .filter(|&exposure| exposure > 0. && exposure.is_finite())
+            .product()
+    }
+
+    fn push_line(&mut self, line: &[u8]) {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim_end();
+
+        match line.split_once('=') {
+            Some((name, value))
+                if !line.starts_with('#')
+                    && !name.is_empty()
+                    && !name.contains(char::is_whitespace) =>
+            {
+                self.variables.push((name.into(), value.trim().into()));
+            }
+            _ => self.other.push(line.into()),
+        }
+    }
+}
+
+pub(crate) fn parse_header<R: BufRead>(mut reader: R) -> LoadResult<(usize, usize, Header, R)> {
+    let mut header = Header::default();
+    let mut line = Vec::new();
+
+    // The rest of the line with the magic number isn't part of the header
+    while reader.read_byte()? != EOL {}
+
+    // The first paragraph ends with an empty line
     loop {
-        let mut next_is_eol = || reader.read_byte().map(|b| b == EOL);
-        if next_is_eol()? && next_is_eol()? {
-            break;
+        match reader.read_byte()? {
+            EOL if is_blank_line(&line) => break,
+            EOL => {
+                header.push_line(&line);
+                line.clear();
+            }
+            byte => line.push(byte),
         }
     }
 
-    DimParser::new(reader)?.parse()
+    let (width, height, reader) = DimParser::new(reader)?.parse()?;
+    Ok((width, height, header, reader))
+}
+
+/// Whether a line ends the first paragraph of the header, which holds the variables. Lines may
+/// end with a carriage return, for files that were written with Windows line endings.
+pub(super) fn is_blank_line(line: &[u8]) -> bool {
+    matches!(line, b"" | b"\r")
 }
 
 struct DimParser<R> {
@@ -98,6 +183,9 @@ impl<R: BufRead> DimParser<R> {
     }
 
     fn expect_eol(&mut self) -> LoadResult {
+        if self.byte == b'\r' {
+            self.eat()?;
+        }
         match self.byte {
             EOL => Ok(()),
             _ => Err(LoadError::Header),
@@ -0,0 +1,300 @@
+//! Resumable parsing for data that arrives in pieces, shared by the push-based [`super::Decoder`]
+//! and the [`super::AsyncLoader`]. Every byte is looked at once, no matter how the data is split.
+
+use super::header::{self, EOL};
+use super::{parse_buffered_header, MAGIC};
+use crate::{convert, LoadError, LoadResult, Rgb, Rgbe};
+use alloc::vec::Vec;
+
+/// The progress of parsing a header. The header is collected until the line with the dimensions is
+/// complete, and then parsed as a whole.
+#[derive(Debug, Default)]
+pub(super) struct HeaderState {
+    bytes: Vec<u8>,
+    /// Where the line that is currently being collected starts.
+    line_start: usize,
+    /// Whether the empty line at the end of the variables has been seen.
+    blank_line: bool,
+}
+
+impl HeaderState {
+    /// Take the part of `bytes` that belongs to the header. Returns the number of bytes used, and
+    /// the width and height of the image once the header is complete.
+    pub(super) fn feed(&mut self, bytes: &[u8]) -> LoadResult<(usize, Option<(usize, usize)>)> {
+        for (i, &byte) in bytes.iter().enumerate() {
+            self.bytes.push(byte);
+            if self.bytes.len() == MAGIC.len() && self.bytes[..] != MAGIC[..] {
+                return Err(LoadError::FileFormat);
+            }
+            if byte != EOL {
+                continue;
+            }
+
+            let line = &self.bytes[self.line_start..self.bytes.len() - 1];
+            let magic_line = self.line_start == 0;
+            self.line_start = self.bytes.len();
+
+            if self.blank_line {
+                // The line with the dimensions is complete
+                let (width, height, _) = parse_buffered_header(&self.bytes)?;
+                return Ok((i + 1, Some((width, height))));
+            } else if !magic_line && header::is_blank_line(line) {
+                self.blank_line = true;
+            }
+        }
+
+        Ok((bytes.len(), None))
+    }
+}
+
+/// The progress of decoding a scanline, with either run-length encoding.
+#[derive(Debug)]
+pub(super) struct ScanlineState {
+    width: usize,
+    /// The RGBE data of the scanline so far, one plane per channel, which is only converted once
+    /// the scanline is complete.
+    planes: Vec<u8>,
+    /// A pixel that has only partly arrived.
+    pixel: [u8; 4],
+    pixel_len: usize,
+    step: Step,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    /// The first pixel, which tells the two encodings apart.
+    Start,
+    /// The byte that starts a run or a copy in the given channel.
+    Code { channel: usize, position: usize },
+    /// The byte that is repeated `count` times.
+    Run {
+        channel: usize,
+        position: usize,
+        count: usize,
+    },
+    /// The `count` bytes that are copied as they are.
+    Copy {
+        channel: usize,
+        position: usize,
+        count: usize,
+    },
+    /// A pixel of the old encoding, which is either literal or repeats the previous one.
+    Old {
+        position: usize,
+        shift: u32,
+        previous: [u8; 4],
+    },
+}
+
+impl ScanlineState {
+    pub(super) fn new(width: usize) -> Self {
+        Self {
+            width,
+            planes: Vec::new(),
+            pixel: [0; 4],
+            pixel_len: 0,
+            step: Step::Start,
+        }
+    }
+
+    /// Decode as much of the next scanline as `bytes` holds. Returns the number of bytes used,
+    /// and whether the scanline is complete, in which case the first `width` pixels of `scanline`
+    /// have been written.
+    pub(super) fn decode(
+        &mut self,
+        bytes: &[u8],
+        scanline: &mut [Rgb],
+    ) -> LoadResult<(usize, bool)> {
+        const MIN_LEN: usize = 8;
+        const MAX_LEN: usize = 0x7fff;
+
+        let width = self.width;
+        let mut used = 0;
+
+        loop {
+            match self.step {
+                Step::Start if width == 0 => return Ok((used, true)),
+                Step::Start => {
+                    let pixel = match self.take_pixel(bytes, &mut used) {
+                        Some(pixel) => pixel,
+                        None => return Ok((used, false)),
+                    };
+                    self.planes.resize(width * 4, 0);
+
+                    if (MIN_LEN..=MAX_LEN).contains(&width)
+                        && Rgbe::from(pixel).is_new_decrunch_marker()
+                    {
+                        self.step = Step::Code {
+                            channel: 0,
+                            position: 0,
+                        };
+                    } else {
+                        self.fill(0, 1, pixel);
+                        self.step = Step::Old {
+                            position: 1,
+                            shift: 0,
+                            previous: pixel,
+                        };
+                    }
+                }
+                Step::Code { channel: 4, .. } => {
+                    self.finish(scanline);
+                    return Ok((used, true));
+                }
+                Step::Code { channel, position } if position == width => {
+                    self.step = Step::Code {
+                        channel: channel + 1,
+                        position: 0,
+                    };
+                }
+                Step::Code { channel, position } => {
+                    let code = match bytes.get(used) {
+                        Some(&code) => code as usize,
+                        None => return Ok((used, false)),
+                    };
+                    used += 1;
+
+                    let (count, run) = if code > 128 {
+                        (code & 127, true)
+                    } else {
+                        (code, false)
+                    };
+                    if count > width - position {
+                        return Err(LoadError::Rle);
+                    }
+
+                    self.step = if run {
+                        Step::Run {
+                            channel,
+                            position,
+                            count,
+                        }
+                    } else {
+                        Step::Copy {
+                            channel,
+                            position,
+                            count,
+                        }
+                    };
+                }
+                Step::Run {
+                    channel,
+                    position,
+                    count,
+                } => {
+                    let value = match bytes.get(used) {
+                        Some(&value) => value,
+                        None => return Ok((used, false)),
+                    };
+                    used += 1;
+
+                    let start = channel * width + position;
+                    self.planes[start..start + count].fill(value);
+                    self.step = Step::Code {
+                        channel,
+                        position: position + count,
+                    };
+                }
+                Step::Copy {
+                    channel,
+                    position,
+                    count,
+                } => {
+                    let available = count.min(bytes.len() - used);
+                    if available == 0 && count > 0 {
+                        return Ok((used, false));
+                    }
+
+                    let start = channel * width + position;
+                    self.planes[start..start + available]
+                        .copy_from_slice(&bytes[used..used + available]);
+                    used += available;
+
+                    self.step = if available == count {
+                        Step::Code {
+                            channel,
+                            position: position + count,
+                        }
+                    } else {
+                        Step::Copy {
+                            channel,
+                            position: position + available,
+                            count: count - available,
+                        }
+                    };
+                }
+                Step::Old { position, .. } if position >= width => {
+                    self.finish(scanline);
+                    return Ok((used, true));
+                }
+                Step::Old {
+                    position,
+                    shift,
+                    previous,
+                } => {
+                    let pixel = match self.take_pixel(bytes, &mut used) {
+                        Some(pixel) => pixel,
+                        None => return Ok((used, false)),
+                    };
+
+                    if Rgbe::from(pixel).is_rle_marker() {
+                        let count = usize::checked_shl(1, shift)
+                            .and_then(|shift_factor| {
+                                usize::from(pixel[3]).checked_mul(shift_factor)
+                            })
+                            .filter(|&count| count <= width - position)
+                            .ok_or(LoadError::Rle)?;
+
+                        self.fill(position, count, previous);
+                        self.step = Step::Old {
+                            position: position + count,
+                            shift: shift + 8,
+                            previous,
+                        };
+                    } else {
+                        self.fill(position, 1, pixel);
+                        self.step = Step::Old {
+                            position: position + 1,
+                            shift: 0,
+                            previous: pixel,
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Collect the bytes of a pixel, returning it once all four have arrived.
+    fn take_pixel(&mut self, bytes: &[u8], used: &mut usize) -> Option<[u8; 4]> {
+        let available = (4 - self.pixel_len).min(bytes.len() - *used);
+        self.pixel[self.pixel_len..self.pixel_len + available]
+            .copy_from_slice(&bytes[*used..*used + available]);
+        self.pixel_len += available;
+        *used += available;
+
+        if self.pixel_len == 4 {
+            self.pixel_len = 0;
+            Some(self.pixel)
+        } else {
+            None
+        }
+    }
+
+    /// Write `count` copies of a pixel into the planes, starting at `position`.
+    fn fill(&mut self, position: usize, count: usize, pixel: [u8; 4]) {
+        for (plane, value) in self.planes.chunks_exact_mut(self.width).zip(pixel) {
+            plane[position..position + count].fill(value);
+        }
+    }
+
+    /// Convert the complete scanline, and get ready for the next one.
+    fn finish(&mut self, scanline: &mut [Rgb]) {
+        let width = self.width;
+        let mut channels = self.planes.chunks_exact(width);
+        let mut channel = || channels.next().unwrap_or_default();
+        let planes = [channel(), channel(), channel(), channel()];
+        convert::rgbe_to_rgb(planes, &mut scanline[..width]);
+
+        self.step = Step::Start;
+    }
+}
@@ -0,0 +1,185 @@
+use super::{Loader, ScanlinesLoader, Window};
+use crate::io::{BufRead, Error as IoError};
+use crate::{Image, LoadError, LoadResult, Rgb};
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::ops::Range;
+
+/// How [`Loader::load_image_lenient`] fills in scanlines that could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fill {
+    /// Fill the scanline with black pixels.
+    #[default]
+    Black,
+    /// Copy the scanline above it, or use black pixels for the first scanline.
+    PreviousScanline,
+}
+
+/// What went wrong with the scanlines named by a [`ScanlineWarning`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanlineProblem {
+    /// The scanline contained invalid run-length encoding.
+    Corrupt,
+    /// The file ended, or could not be resynchronised after a corrupt scanline, before these
+    /// scanlines were read.
+    Missing,
+}
+
+/// A range of scanlines that was filled in by [`Loader::load_image_lenient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanlineWarning {
+    /// The affected rows, counting from the top of the image.
+    pub rows: Range<usize>,
+    /// Why the rows had to be filled in.
+    pub problem: ScanlineProblem,
+}
+
+impl fmt::Display for ScanlineWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let problem = match self.problem {
+            ScanlineProblem::Corrupt => "contained invalid run-length encoding",
+            ScanlineProblem::Missing => "are missing",
+        };
+
+        if self.rows.len() == 1 {
+            write!(f, "row {} {}", self.rows.start, problem)
+        } else {
+            write!(
+                f,
+                "rows {} to {} {}",
+                self.rows.start,
+                self.rows.end - 1,
+                problem
+            )
+        }
+    }
+}
+
+/// An image returned by [`Loader::load_image_lenient`], along with the problems that were
+/// recovered from while loading it.
+#[derive(Debug, Clone)]
+pub struct Recovered {
+    /// The decoded image, with any bad or missing scanlines filled in.
+    pub image: Image,
+    /// The scanlines that were filled in, in order from the top of the image. This is empty if
+    /// the image loaded without problems.
+    pub warnings: Vec<ScanlineWarning>,
+}
+
+impl<R: BufRead> Loader<R> {
+    /// Load an entire [`Image`], recovering from truncated files and invalid run-length encoding
+    /// instead of failing. Scanlines that could not be decoded are filled in as described by
+    /// `fill`, and named in the returned warnings.
+    ///
+    /// After a corrupt scanline that uses the new run-length encoding, loading carries on from the
+    /// start of the next scanline that can be found. Other encodings can't be resynchronised, so
+    /// every scanline after a corrupt one is treated as missing. Errors in the header, and I/O
+    /// errors other than the file ending early, are still returned.
+    pub fn load_image_lenient(self, fill: Fill) -> Result<Recovered, IoError> {
+        let &Self { width, height, .. } = &self;
+        let length = width.checked_mul(height).ok_or(LoadError::Header)?;
+
+        let mut data = vec![Rgb::zero(); length];
+        let mut warnings = Vec::new();
+
+        if length != 0 {
+            let mut scanlines = self.scanlines();
+            let mut warn = |data: &mut [Rgb], rows: Range<usize>, problem| {
+                fill_rows(data, width, rows.clone(), fill);
+                warnings.push(ScanlineWarning { rows, problem });
+            };
+
+            // Whether the marker at the start of the next scanline has already been consumed
+            let mut resynced = false;
+            let mut y = 0;
+
+            while y < height {
+                let row = &mut data[y * width..(y + 1) * width];
+                let decoded = if resynced {
+                    scanlines.new_decrunch(&mut Window {
+                        start: 0,
+                        window: row,
+                    })
+                } else {
+                    scanlines.decode_scanline(0, row)
+                };
+                resynced = false;
+
+                match decoded {
+                    Ok(()) => y += 1,
+                    Err(LoadError::Eof) => {
+                        warn(&mut data, y..height, ScanlineProblem::Missing);
+                        break;
+                    }
+                    Err(LoadError::Rle) => {
+                        warn(&mut data, y..y + 1, ScanlineProblem::Corrupt);
+                        y += 1;
+
+                        if y < height {
+                            resynced = scanlines.resync()?;
+                            if !resynced {
+                                warn(&mut data, y..height, ScanlineProblem::Missing);
+                                break;
+                            }
+                        }
+                    }
+                    Err(error) => return Err(error.into()),
+                }
+            }
+        }
+
+        Ok(Recovered {
+            image: Image {
+                width,
+                height,
+                data,
+            },
+            warnings,
+        })
+    }
+}
+
+impl<R: BufRead> ScanlinesLoader<R> {
+    /// Skip ahead to just past the next new run-length encoding marker for this width, returning
+    /// whether one was found.
+    fn resync(&mut self) -> LoadResult<bool> {
+        const MIN_LEN: usize = 8;
+        const MAX_LEN: usize = 0x7fff;
+
+        if !(MIN_LEN..=MAX_LEN).contains(&self.width) {
+            return Ok(false);
+        }
+
+        let marker = [2, 2, (self.width >> 8) as u8, self.width as u8];
+        let mut recent = [0u8; 4];
+
+        loop {
+            let buf = self.reader.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(false);
+            }
+
+            let found = buf.iter().position(|&byte| {
+                recent = [recent[1], recent[2], recent[3], byte];
+                recent == marker
+            });
+
+            let used = found.map_or(buf.len(), |i| i + 1);
+            self.reader.consume(used);
+
+            if found.is_some() {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+fn fill_rows(data: &mut [Rgb], width: usize, rows: Range<usize>, fill: Fill) {
+    for y in rows {
+        let start = y * width;
+        match fill {
+            Fill::PreviousScanline if y > 0 => data.copy_within(start - width..start, start),
+            _ => data[start..start + width].fill(Rgb::zero()),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use super::{Loader, ScanlinesLoader};
+use crate::Rgb;
+use std::convert::TryInto;
+use std::io::{BufRead, Error as IoError, ErrorKind, Read, Seek, SeekFrom, Write};
+
+const INDEX_MAGIC: &[u8; 8] = b"#?RADIDX";
+
+/// The byte offsets at which each scanline of an image starts, which makes it possible to decode
+/// scanlines in any order. Build one with [`Loader::index_scanlines`], or load one that was saved
+/// earlier with [`ScanlineIndex::read_from`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanlineIndex {
+    width: usize,
+    offsets: Vec<u64>,
+}
+
+impl ScanlineIndex {
+    /// The width of the indexed image.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the indexed image, i.e. the number of scanlines.
+    pub fn height(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// The offset of each scanline, in bytes from the start of the stream.
+    pub fn offsets(&self) -> &[u64] {
+        &self.offsets
+    }
+
+    /// Save the index, so that it can be loaded again later with [`ScanlineIndex::read_from`].
+    ///
+    /// The format is an eight byte magic number, followed by the width, the height and then the
+    /// offset of each scanline, all as little-endian 64-bit integers.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+        writer.write_all(INDEX_MAGIC)?;
+        writer.write_all(&(self.width as u64).to_le_bytes())?;
+        writer.write_all(&(self.offsets.len() as u64).to_le_bytes())?;
+        for offset in &self.offsets {
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Load an index that was saved with [`ScanlineIndex::write_to`].
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, IoError> {
+        let invalid = || IoError::new(ErrorKind::InvalidData, "the scanline index is invalid");
+
+        let mut magic = [0u8; INDEX_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != INDEX_MAGIC {
+            return Err(invalid());
+        }
+
+        let mut read_u64 = || -> Result<u64, IoError> {
+            let mut buf = [0u8; 8];
+            reader.read_exact(&mut buf)?;
+            Ok(u64::from_le_bytes(buf))
+        };
+
+        let width = read_u64()?.try_into().map_err(|_| invalid())?;
+        let height: usize = read_u64()?.try_into().map_err(|_| invalid())?;
+        let mut offsets = Vec::new();
+        for _ in 0..height {
+            offsets.push(read_u64()?);
+        }
+
+        Ok(Self { width, offsets })
+    }
+}
+
+impl<R: BufRead + Seek> Loader<R> {
+    /// Scan through the image data once, without decoding it, to find where every scanline
+    /// starts. The resulting [`RandomAccessLoader`] can then decode any scanline on demand.
+    pub fn index_scanlines(self) -> Result<RandomAccessLoader<R>, IoError> {
+        let mut scanlines = self.scanlines();
+        let mut offsets = Vec::with_capacity(scanlines.height);
+
+        for _ in 0..scanlines.height {
+            offsets.push(scanlines.reader.stream_position()?);
+            scanlines.skip_scanline()?;
+        }
+
+        let index = ScanlineIndex {
+            width: scanlines.width,
+            offsets,
+        };
+
+        Ok(RandomAccessLoader {
+            scanlines,
+            index,
+            next: None,
+        })
+    }
+
+    /// Reuse a [`ScanlineIndex`] that was built earlier for the same image, skipping the scan
+    /// pass. If the index does not match the dimensions of the image, an error of the kind
+    /// [`std::io::ErrorKind::InvalidInput`] will be returned.
+    pub fn with_index(self, index: ScanlineIndex) -> Result<RandomAccessLoader<R>, IoError> {
+        if index.width != self.width || index.height() != self.height {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "the scanline index does not match the dimensions of the image",
+            ));
+        }
+
+        Ok(RandomAccessLoader {
+            scanlines: self.scanlines(),
+            index,
+            next: Some(0),
+        })
+    }
+}
+
+/// An image loader that can decode scanlines in any order, by seeking to the offsets recorded in a
+/// [`ScanlineIndex`].
+/// ```rust
+/// use radiant::{Loader, Rgb};
+/// use std::io::BufReader;
+/// use std::fs::File;
+///
+/// let f = File::open("assets/colorful_studio_2k.hdr").expect("failed to open file");
+/// let f = BufReader::new(f);
+/// let mut loader = Loader::new(f)
+///     .expect("failed to read image")
+///     .index_scanlines()
+///     .expect("failed to index image");
+///
+/// let mut buffer = vec![Rgb::zero(); loader.width()];
+/// loader.read_scanline(512, &mut buffer).expect("failed to read image");
+/// ```
+pub struct RandomAccessLoader<R> {
+    scanlines: ScanlinesLoader<R>,
+    index: ScanlineIndex,
+    /// The scanline that the reader is currently positioned at, if known.
+    next: Option<usize>,
+}
+
+impl<R: BufRead + Seek> RandomAccessLoader<R> {
+    /// The width of the image.
+    pub fn width(&self) -> usize {
+        self.scanlines.width
+    }
+
+    /// The height of the image, i.e. the number of scanlines.
+    pub fn height(&self) -> usize {
+        self.scanlines.height
+    }
+
+    /// The index of scanline offsets, which can be saved and reused with [`Loader::with_index`].
+    pub fn index(&self) -> &ScanlineIndex {
+        &self.index
+    }
+
+    /// Decode scanline `y` of the image. The provided scanline buffer must be at least as long as
+    /// the width of the image, and `y` must be less than the height, otherwise an error of the
+    /// kind [`std::io::ErrorKind::InvalidInput`] will be returned.
+    pub fn read_scanline(&mut self, y: usize, scanline: &mut [Rgb]) -> Result<(), IoError> {
+        let out_of_bounds = || IoError::new(ErrorKind::InvalidInput, "scanline is out of bounds");
+        let &offset = self.index.offsets.get(y).ok_or_else(out_of_bounds)?;
+
+        // Reading scanlines in order needs no seeking, which would throw away buffered data
+        if self.next.take() != Some(y) {
+            self.scanlines.reader.seek(SeekFrom::Start(offset))?;
+        }
+
+        self.scanlines.read_scanline(scanline)?;
+        self.next = Some(y + 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use super::{Loader, ScanlinesLoader};
+use crate::io::Error as IoError;
+use crate::{convert, Image, LoadError, LoadResult, Rgb, Rgbe};
+use alloc::{vec, vec::Vec};
+
+/// Decode an image held entirely in memory. Scanlines that use the new run-length encoding are
+/// decoded straight out of the slice into a planar scratch buffer, and converted to floats in a
+/// single pass afterwards. Anything else falls back to [`ScanlinesLoader`].
+pub(crate) fn load_slice(bytes: &[u8]) -> Result<Image, IoError> {
+    let Loader {
+        width,
+        height,
+        reader,
+        ..
+    } = Loader::new(bytes)?;
+    let length = width.checked_mul(height).ok_or(LoadError::Header)?;
+
+    let mut data = vec![Rgb::zero(); length];
+
+    if length != 0 {
+        let mut decoder = SliceDecoder {
+            bytes: reader,
+            width,
+            planes: vec![0; width * 4],
+        };
+
+        for scanline in data.chunks_exact_mut(width) {
+            decoder.decode_scanline(scanline)?;
+        }
+    }
+
+    Ok(Image {
+        width,
+        height,
+        data,
+    })
+}
+
+struct SliceDecoder<'a> {
+    bytes: &'a [u8],
+    width: usize,
+    /// The four channels of one scanline, stored one after another.
+    planes: Vec<u8>,
+}
+
+impl SliceDecoder<'_> {
+    fn decode_scanline(&mut self, scanline: &mut [Rgb]) -> LoadResult {
+        const MIN_LEN: usize = 8;
+        const MAX_LEN: usize = 0x7fff;
+
+        let is_new_decrunch = match self.bytes {
+            [r, g, b, e, ..] => Rgbe::from([*r, *g, *b, *e]).is_new_decrunch_marker(),
+            _ => false,
+        };
+
+        if (MIN_LEN..=MAX_LEN).contains(&self.width) && is_new_decrunch {
+            self.bytes = &self.bytes[4..];
+            self.new_decrunch(scanline)
+        } else {
+            let mut fallback = ScanlinesLoader {
+                width: self.width,
+                height: 1,
+                reader: self.bytes,
+                planes: Vec::new(),
+            };
+            fallback.decode_scanline(0, scanline)?;
+            self.bytes = fallback.reader;
+            Ok(())
+        }
+    }
+
+    fn new_decrunch(&mut self, scanline: &mut [Rgb]) -> LoadResult {
+        let width = self.width;
+        let mut bytes = self.bytes;
+
+        for plane in self.planes.chunks_exact_mut(width) {
+            let mut position = 0;
+            while position < width {
+                let (&code, rest) = bytes.split_first().ok_or(LoadError::Eof)?;
+                let code = code as usize;
+                if code > 128 {
+                    // run
+                    let (&val, rest) = rest.split_first().ok_or(LoadError::Eof)?;
+                    let count = code & 127;
+                    plane
+                        .get_mut(position..position + count)
+                        .ok_or(LoadError::Rle)?
+                        .fill(val);
+
+                    bytes = rest;
+                    position += count;
+                } else {
+                    // non-run
+                    let vals = rest.get(..code).ok_or(LoadError::Eof)?;
+                    plane
+                        .get_mut(position..position + code)
+                        .ok_or(LoadError::Rle)?
+                        .copy_from_slice(vals);
+
+                    bytes = &rest[code..];
+                    position += code;
+                }
+            }
+        }
+
+        self.bytes = bytes;
+
+        let (r, rest) = self.planes.split_at(width);
+        let (g, rest) = rest.split_at(width);
+        let (b, e) = rest.split_at(width);
+        convert::rgbe_to_rgb([r, g, b, e], scanline);
+
+        Ok(())
+    }
+}
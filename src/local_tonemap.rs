@@ -0,0 +1,274 @@
+//! Local tone mapping operators, which compress the dynamic range of an image differently from
+//! place to place, so that detail stays visible in both the shadows and the highlights. Requires
+//! the "std" feature.
+//!
+//! Both operators finish with a global [`ToneMap`].
+
+use crate::blur::Edges;
+use crate::blur::{downsample, gaussian_blur, resize};
+use crate::tonemap::ToneMap;
+use crate::{Image, Rgb};
+
+/// Durand and Dorsey's local tone mapping operator, from "Fast Bilateral Filtering for the Display
+/// of High-Dynamic-Range Images" (2002).
+///
+/// The log luminance is split into a base layer with an edge-preserving bilateral filter, and a
+/// detail layer with everything else. Only the contrast of the base layer is compressed, so
+/// textures stay visible in both the shadows and the highlights. The brightest part of the base
+/// layer is mapped to 1, and the result is finished with [`BilateralToneMap::tone_map`]. Requires
+/// the "std" feature.
+/// ```rust
+/// use radiant::local_tonemap::BilateralToneMap;
+/// use radiant::{Image, Rgb};
+///
+/// let dark = Rgb { r: 0.01, g: 0.01, b: 0.01 };
+/// let bright = Rgb { r: 1000.0, g: 1000.0, b: 1000.0 };
+/// let image = Image { width: 2, height: 1, data: vec![dark, bright] };
+///
+/// let display = BilateralToneMap::default().apply(&image);
+/// assert!(display.data[0].r > 0.1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BilateralToneMap {
+    /// The ratio between the brightest and darkest parts of the base layer after compression.
+    pub contrast: f32,
+    /// The standard deviation of the spatial part of the bilateral filter, as a fraction of the
+    /// larger dimension of the image.
+    pub spatial_sigma: f32,
+    /// The standard deviation of the range part of the bilateral filter, in decades of luminance.
+    /// Edges with a larger contrast than this are preserved in the base layer.
+    pub range_sigma: f32,
+    /// The global tone map that is applied after the local compression.
+    pub tone_map: ToneMap,
+}
+
+impl Default for BilateralToneMap {
+    /// The parameters from the paper: a contrast of 5, a spatial sigma of 2% of the image size,
+    /// and a range sigma of 0.4, with the default [`ToneMap`].
+    fn default() -> Self {
+        Self {
+            contrast: 5.,
+            spatial_sigma: 0.02,
+            range_sigma: 0.4,
+            tone_map: ToneMap::default(),
+        }
+    }
+}
+
+impl BilateralToneMap {
+    /// Tone map an image to display values between 0 and 1.
+    pub fn apply(&self, image: &Image) -> Image {
+        let mut compressed = self.compress(image);
+        compressed.map(|rgb| self.tone_map.apply(rgb));
+        compressed
+    }
+
+    /// Tone map an image into 8-bit pixels, in the same order as [`Image::data`].
+    pub fn apply_rgb8(&self, image: &Image) -> Vec<[u8; 3]> {
+        self.compress(image).to_rgb8(self.tone_map)
+    }
+
+    /// Compress the contrast of the base layer, leaving linear values.
+    fn compress(&self, image: &Image) -> Image {
+        let logs: Vec<f32> = image
+            .data
+            .iter()
+            .map(|&rgb| rgb.relative_luminance().max(1e-6).log10())
+            .collect();
+        let base = self.bilateral_filter(image.width, image.height, &logs);
+
+        let (min, max) = base
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &b| {
+                (min.min(b), max.max(b))
+            });
+        let range = max - min;
+        let factor = if range > 0. {
+            self.contrast.log10() / range
+        } else {
+            1.
+        };
+
+        let mut compressed = image.clone();
+        for ((rgb, &log), &base) in compressed.data.iter_mut().zip(&logs).zip(&base) {
+            // Compress the base layer so that its maximum maps to 1, and keep the detail
+            let detail = log - base;
+            let compressed_log = (base - max) * factor + detail;
+            *rgb *= 10_f32.powf(compressed_log - log);
+        }
+        compressed
+    }
+
+    /// Filter the log luminance with Durand and Dorsey's piecewise linear approximation: the image
+    /// is filtered with a Gaussian at a set of fixed intensities, and each pixel interpolates
+    /// between the results for the two intensities nearest to its own.
+    fn bilateral_filter(&self, width: usize, height: usize, logs: &[f32]) -> Vec<f32> {
+        let (min, max) = logs
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &l| {
+                (min.min(l), max.max(l))
+            });
+        if max <= min || !(max - min).is_finite() || self.range_sigma <= 0. {
+            return logs.to_vec();
+        }
+
+        let levels = ((max - min) / self.range_sigma).ceil() as usize + 1;
+        let step = (max - min) / (levels - 1) as f32;
+        let spatial_sigma = self.spatial_sigma * width.max(height) as f32;
+
+        let mut base = vec![0.; logs.len()];
+        for level in 0..levels {
+            let intensity = min + level as f32 * step;
+
+            // The range weight of every pixel, and its weighted intensity
+            let (weights, weighted): (Vec<f32>, Vec<f32>) = logs
+                .iter()
+                .map(|&l| {
+                    let d = (l - intensity) / self.range_sigma;
+                    let w = (-0.5 * d * d).exp();
+                    (w, w * l)
+                })
+                .unzip();
+            let (weights, weighted) = (
+                blur_downsampled(&weights, width, height, spatial_sigma),
+                blur_downsampled(&weighted, width, height, spatial_sigma),
+            );
+
+            let filtered = weights.iter().zip(&weighted);
+            for ((b, &l), (&w, &wl)) in base.iter_mut().zip(logs).zip(filtered) {
+                let interpolation = 1. - ((l - intensity) / step).abs();
+                if interpolation > 0. {
+                    let value = if w > 1e-12 { wl / w } else { l };
+                    *b += interpolation * value;
+                }
+            }
+        }
+
+        base
+    }
+}
+
+/// Blur with a Gaussian at a lower resolution where the filter is wide enough not to need the
+/// detail, and scale the result back up.
+fn blur_downsampled(data: &[f32], width: usize, height: usize, mut sigma: f32) -> Vec<f32> {
+    let (mut level_width, mut level_height) = (width, height);
+    let mut level = data.to_vec();
+    while sigma > 4. && level_width > 8 && level_height > 8 {
+        (level_width, level_height, level) =
+            downsample(&level, level_width, level_height, Edges::Clamp);
+        sigma /= 2.;
+    }
+
+    let blurred = gaussian_blur(&level, level_width, level_height, sigma, Edges::Clamp);
+    resize(
+        &blurred,
+        level_width,
+        level_height,
+        width,
+        height,
+        Edges::Clamp,
+    )
+}
+
+/// Reinhard et al.'s photographic tone reproduction with automatic dodging and burning, from
+/// "Photographic Tone Reproduction for Digital Images" (2002).
+///
+/// The image is scaled so that its log-average luminance maps to the key, and every pixel is then
+/// compressed by the average luminance of the largest surrounding area that has no strong edges,
+/// much like a photographer dodging and burning a print. The result is finished with
+/// [`PhotographicToneMap::tone_map`]. Requires the "std" feature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhotographicToneMap {
+    /// The display luminance that the log-average luminance of the image maps to, like the
+    /// middle gray of 0.18 in photography.
+    pub key: f32,
+    /// How sharp the edges between areas that are dodged or burnt differently are.
+    pub sharpening: f32,
+    /// How much the surrounding area may vary before it counts as an edge.
+    pub threshold: f32,
+    /// How many scales to consider, each 1.6 times larger than the one before, starting at one
+    /// pixel.
+    pub scales: usize,
+    /// The global tone map that is applied after the local compression.
+    pub tone_map: ToneMap,
+}
+
+impl Default for PhotographicToneMap {
+    /// The parameters from the paper: a key of 0.18, sharpening of 8, a threshold of 0.05 and
+    /// eight scales, with the default [`ToneMap`].
+    fn default() -> Self {
+        Self {
+            key: 0.18,
+            sharpening: 8.,
+            threshold: 0.05,
+            scales: 8,
+            tone_map: ToneMap::default(),
+        }
+    }
+}
+
+impl PhotographicToneMap {
+    /// Tone map an image to display values between 0 and 1.
+    pub fn apply(&self, image: &Image) -> Image {
+        let mut compressed = self.compress(image);
+        compressed.map(|rgb| self.tone_map.apply(rgb));
+        compressed
+    }
+
+    /// Tone map an image into 8-bit pixels, in the same order as [`Image::data`].
+    pub fn apply_rgb8(&self, image: &Image) -> Vec<[u8; 3]> {
+        self.compress(image).to_rgb8(self.tone_map)
+    }
+
+    /// Dodge and burn the scaled luminance, leaving linear values.
+    fn compress(&self, image: &Image) -> Image {
+        let luminances: Vec<f32> = image
+            .data
+            .iter()
+            .map(|&rgb| rgb.relative_luminance())
+            .collect();
+        let count = luminances.len().max(1) as f32;
+        let log_average = (luminances
+            .iter()
+            .map(|&l| (1e-4 + l.max(0.)).ln())
+            .sum::<f32>()
+            / count)
+            .exp();
+        let scale = self.key / log_average;
+
+        let scaled: Vec<f32> = luminances.iter().map(|&l| l.max(0.) * scale).collect();
+
+        // The center-surround function at scale s compares blurs with sigmas of s / 4 and
+        // 1.6 * s / 4, so consecutive scales share their blurs
+        let (width, height) = (image.width, image.height);
+        let blurs: Vec<Vec<f32>> = (0..=self.scales)
+            .map(|i| {
+                let sigma = 1.6_f32.powi(i as i32) / 4.;
+                gaussian_blur(&scaled, width, height, sigma, Edges::Clamp)
+            })
+            .collect();
+
+        let mut compressed = image.clone();
+        for (i, rgb) in compressed.data.iter_mut().enumerate() {
+            let mut surround = blurs[0][i];
+            for scale in 0..self.scales {
+                let s = 1.6_f32.powi(scale as i32);
+                let (center, wider) = (blurs[scale][i], blurs[scale + 1][i]);
+                let activity =
+                    (center - wider) / (self.sharpening.exp2() * self.key / (s * s) + center);
+                if activity.abs() >= self.threshold {
+                    break;
+                }
+                surround = center;
+            }
+
+            let display = scaled[i] / (1. + surround);
+            *rgb = if luminances[i] > 0. {
+                *rgb * (display / luminances[i])
+            } else {
+                Rgb::zero()
+            };
+        }
+        compressed
+    }
+}
@@ -0,0 +1,250 @@
+//! Colour grading with 3D lookup tables in the Adobe/Resolve `.cube` format. Requires the "std"
+//! feature.
+//!
+//! A `.cube` file lists the output colour for every point of an evenly spaced grid over the input
+//! colours, with red changing fastest and blue slowest. Colours between the grid points are
+//! interpolated. Most grades expect display-referred input between 0 and 1, so scene-linear HDR
+//! images should go through a [`Log2Shaper`] first, unless the grade was made for linear input.
+//! ```rust
+//! use radiant::lut::{self, Interpolation, Log2Shaper};
+//! use radiant::{Image, Rgb};
+//!
+//! let cube = "LUT_3D_SIZE 2\n\
+//!     0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1\n";
+//! let mut lut = lut::load(cube.as_bytes()).expect("invalid LUT");
+//! lut.interpolation = Interpolation::Tetrahedral;
+//! lut.shaper = Some(Log2Shaper { min: -8.0, max: 4.0 });
+//!
+//! let image = Image { width: 1, height: 1, data: vec![Rgb { r: 1.0, g: 1.0, b: 1.0 }] };
+//! let graded = lut.apply(&image);
+//! assert!((graded.data[0].g - 8.0 / 12.0).abs() < 1e-6);
+//! ```
+
+use crate::{Image, Rgb};
+use std::io::{BufRead, Error as IoError, ErrorKind};
+
+/// How colours between the points of a [`Lut`] are interpolated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Blend the eight corners of the surrounding cube.
+    #[default]
+    Trilinear,
+    /// Blend the four corners of the surrounding tetrahedron, which keeps the neutral axis
+    /// neutral and is what most grading applications use.
+    Tetrahedral,
+}
+
+/// A logarithmic shaper for scene-linear input. The range from `2^min` to `2^max` is mapped onto
+/// the domain of the LUT evenly in stops, and values outside of it are clamped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Log2Shaper {
+    /// The log2 of the darkest value, which maps to the minimum of the domain.
+    pub min: f32,
+    /// The log2 of the brightest value, which maps to the maximum of the domain.
+    pub max: f32,
+}
+
+impl Log2Shaper {
+    /// Map a linear value to a position between 0 and 1.
+    fn apply(&self, c: f32) -> f32 {
+        let t = (c.max(f32::MIN_POSITIVE).log2() - self.min) / (self.max - self.min);
+        t.clamp(0., 1.)
+    }
+}
+
+/// A 3D lookup table, from [`load`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut {
+    /// The title of the LUT, if it has one.
+    pub title: Option<String>,
+    /// The number of grid points along each axis.
+    pub size: usize,
+    /// The output colours at the grid points, with red changing fastest and blue slowest.
+    pub data: Vec<Rgb>,
+    /// The input colour at the first grid point, 0 by default.
+    pub domain_min: Rgb,
+    /// The input colour at the last grid point, 1 by default.
+    pub domain_max: Rgb,
+    /// How colours between grid points are interpolated.
+    pub interpolation: Interpolation,
+    /// The shaper applied to input colours before the lookup, if any.
+    pub shaper: Option<Log2Shaper>,
+}
+
+/// Load a 3D LUT from a `.cube` file. Files that only contain a 1D LUT are not supported, and the
+/// 1D part of files that contain both is ignored, as are unknown keywords.
+pub fn load<R: BufRead>(reader: R) -> Result<Lut, IoError> {
+    let mut lut = Lut {
+        title: None,
+        size: 0,
+        data: Vec::new(),
+        domain_min: Rgb::zero(),
+        domain_max: Rgb {
+            r: 1.,
+            g: 1.,
+            b: 1.,
+        },
+        interpolation: Interpolation::default(),
+        shaper: None,
+    };
+    let mut size_1d = 0;
+    let mut data_1d = 0;
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        match keyword {
+            "TITLE" => lut.title = Some(rest.trim_matches('"').into()),
+            "LUT_3D_SIZE" => {
+                lut.size = rest
+                    .parse()
+                    .ok()
+                    .filter(|&size| (2..=256).contains(&size))
+                    .ok_or_else(|| invalid_data("the LUT size is invalid"))?;
+            }
+            "LUT_1D_SIZE" => {
+                size_1d = rest
+                    .parse()
+                    .map_err(|_| invalid_data("the LUT size is invalid"))?
+            }
+            "DOMAIN_MIN" => lut.domain_min = parse_rgb(rest)?,
+            "DOMAIN_MAX" => lut.domain_max = parse_rgb(rest)?,
+            "LUT_3D_INPUT_RANGE" => {
+                let (min, max) = parse_range(rest)?;
+                lut.domain_min = min;
+                lut.domain_max = max;
+            }
+            _ if keyword.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+                let rgb = parse_rgb(line)?;
+                // In files with both, the 1D table comes first
+                if data_1d < size_1d {
+                    data_1d += 1;
+                } else {
+                    lut.data.push(rgb);
+                }
+            }
+            // Other keywords, like LUT_1D_INPUT_RANGE, don't affect the 3D table
+            _ => {}
+        }
+    }
+
+    if lut.size == 0 {
+        return Err(invalid_data("the file does not contain a 3D LUT"));
+    }
+    if lut.data.len() != lut.size.pow(3) {
+        return Err(invalid_data("the LUT has the wrong number of entries"));
+    }
+    Ok(lut)
+}
+
+impl Lut {
+    /// Look up a single colour.
+    pub fn lookup(&self, rgb: Rgb) -> Rgb {
+        let position = |c: f32, min: f32, max: f32| {
+            let t = match self.shaper {
+                Some(shaper) => shaper.apply(c),
+                None => (c - min) / (max - min),
+            };
+            let t = if t > 0. { t.min(1.) } else { 0. };
+            t * (self.size - 1) as f32
+        };
+        let (min, max) = (self.domain_min, self.domain_max);
+        let r = position(rgb.r, min.r, max.r);
+        let g = position(rgb.g, min.g, max.g);
+        let b = position(rgb.b, min.b, max.b);
+
+        // The cell that contains the colour, and the position within it
+        let last = self.size - 2;
+        let (r0, g0, b0) = (
+            (r as usize).min(last),
+            (g as usize).min(last),
+            (b as usize).min(last),
+        );
+        let (fr, fg, fb) = (r - r0 as f32, g - g0 as f32, b - b0 as f32);
+        let corner = |dr: usize, dg: usize, db: usize| {
+            self.data[(r0 + dr) + (g0 + dg) * self.size + (b0 + db) * self.size * self.size]
+        };
+
+        match self.interpolation {
+            Interpolation::Trilinear => {
+                let lerp_r = |dg, db| corner(0, dg, db).lerp(corner(1, dg, db), fr);
+                let lower = lerp_r(0, 0).lerp(lerp_r(1, 0), fg);
+                let upper = lerp_r(0, 1).lerp(lerp_r(1, 1), fg);
+                lower.lerp(upper, fb)
+            }
+            Interpolation::Tetrahedral => {
+                let (c000, c111) = (corner(0, 0, 0), corner(1, 1, 1));
+                if fr > fg {
+                    if fg > fb {
+                        let (c100, c110) = (corner(1, 0, 0), corner(1, 1, 0));
+                        c000 + (c100 - c000) * fr + (c110 - c100) * fg + (c111 - c110) * fb
+                    } else if fr > fb {
+                        let (c100, c101) = (corner(1, 0, 0), corner(1, 0, 1));
+                        c000 + (c100 - c000) * fr + (c101 - c100) * fb + (c111 - c101) * fg
+                    } else {
+                        let (c001, c101) = (corner(0, 0, 1), corner(1, 0, 1));
+                        c000 + (c001 - c000) * fb + (c101 - c001) * fr + (c111 - c101) * fg
+                    }
+                } else if fb > fg {
+                    let (c001, c011) = (corner(0, 0, 1), corner(0, 1, 1));
+                    c000 + (c001 - c000) * fb + (c011 - c001) * fg + (c111 - c011) * fr
+                } else if fb > fr {
+                    let (c010, c011) = (corner(0, 1, 0), corner(0, 1, 1));
+                    c000 + (c010 - c000) * fg + (c011 - c010) * fb + (c111 - c011) * fr
+                } else {
+                    let (c010, c110) = (corner(0, 1, 0), corner(1, 1, 0));
+                    c000 + (c010 - c000) * fg + (c110 - c010) * fr + (c111 - c110) * fb
+                }
+            }
+        }
+    }
+
+    /// Grade every pixel of an image.
+    pub fn apply(&self, image: &Image) -> Image {
+        let mut graded = image.clone();
+        graded.map(|rgb| self.lookup(rgb));
+        graded
+    }
+}
+
+fn parse_rgb(values: &str) -> Result<Rgb, IoError> {
+    let mut values = values.split_whitespace().map(str::parse::<f32>);
+    let mut next = || match values.next() {
+        Some(Ok(value)) => Ok(value),
+        _ => Err(invalid_data("the LUT contains an invalid colour")),
+    };
+    Ok(Rgb {
+        r: next()?,
+        g: next()?,
+        b: next()?,
+    })
+}
+
+fn parse_range(values: &str) -> Result<(Rgb, Rgb), IoError> {
+    let mut values = values.split_whitespace().map(str::parse::<f32>);
+    match (values.next(), values.next()) {
+        (Some(Ok(min)), Some(Ok(max))) => Ok((
+            Rgb {
+                r: min,
+                g: min,
+                b: min,
+            },
+            Rgb {
+                r: max,
+                g: max,
+                b: max,
+            },
+        )),
+        _ => Err(invalid_data("the LUT input range is invalid")),
+    }
+}
+
+fn invalid_data(message: &'static str) -> IoError {
+    IoError::new(ErrorKind::InvalidData, message)
+}
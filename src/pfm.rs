@@ -0,0 +1,219 @@
+//! Reading and writing Portable Float Map (.pfm) images. Requires the "std" feature.
+//!
+//! A PFM file starts with `PF` for colour or `Pf` for grayscale, followed by the width, the height
+//! and a scale factor whose sign gives the byte order: negative for little-endian, positive for
+//! big-endian. The pixels follow as raw 32-bit floats, with rows stored from the bottom of the
+//! image to the top.
+//! ```rust
+//! use radiant::{pfm, Image, Rgb};
+//!
+//! let image = Image {
+//!     width: 2,
+//!     height: 1,
+//!     data: vec![Rgb { r: 1.0, g: 0.5, b: 0.25 }; 2],
+//! };
+//!
+//! let mut file = Vec::new();
+//! pfm::Encoder::new(&mut file).encode(&image).expect("failed to encode image");
+//! let loaded = pfm::load(&file[..]).expect("failed to load image");
+//! assert_eq!(loaded.data, image.data);
+//! ```
+
+use crate::{Image, ReadExt, Rgb};
+use std::io::{BufRead, Error as IoError, ErrorKind, Write};
+
+/// The channels stored in a PFM file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channels {
+    /// Red, green and blue, marked by `PF`.
+    Color,
+    /// A single channel, marked by `Pf`.
+    Grayscale,
+}
+
+/// The byte order of the floats in a PFM file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte first, marked by a negative scale factor.
+    Little,
+    /// Most significant byte first, marked by a positive scale factor.
+    Big,
+}
+
+/// Load a PFM image. Grayscale images are loaded with the same value in all three channels. The
+/// scale factor only determines the byte order, and does not affect the pixel values.
+pub fn load<R: BufRead>(mut reader: R) -> Result<Image, IoError> {
+    let channels = match read_token(&mut reader)?.as_slice() {
+        b"PF" => Channels::Color,
+        b"Pf" => Channels::Grayscale,
+        _ => return Err(invalid_data("the file is not a PFM image")),
+    };
+
+    let width = parse_token::<usize, _>(&mut reader)?;
+    let height = parse_token::<usize, _>(&mut reader)?;
+    let scale = parse_token::<f32, _>(&mut reader)?;
+    let endianness = if scale.is_sign_negative() {
+        Endianness::Little
+    } else {
+        Endianness::Big
+    };
+
+    let samples = match channels {
+        Channels::Color => 3,
+        Channels::Grayscale => 1,
+    };
+    let length = width
+        .checked_mul(height)
+        .filter(|length| length.checked_mul(samples * 4).is_some())
+        .ok_or_else(|| invalid_data("the PFM header is invalid"))?;
+
+    let mut data = vec![Rgb::zero(); length];
+    let mut row = vec![0; width * samples * 4];
+
+    // Rows are stored from the bottom up
+    for scanline in data.chunks_exact_mut(width.max(1)).rev() {
+        reader.read_exact(&mut row)?;
+
+        let mut floats = row.chunks_exact(4).map(|bytes| {
+            let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+            match endianness {
+                Endianness::Little => f32::from_le_bytes(bytes),
+                Endianness::Big => f32::from_be_bytes(bytes),
+            }
+        });
+
+        for pixel in scanline {
+            *pixel = match channels {
+                Channels::Color => {
+                    let mut next = || floats.next().unwrap_or_default();
+                    Rgb {
+                        r: next(),
+                        g: next(),
+                        b: next(),
+                    }
+                }
+                Channels::Grayscale => {
+                    let v = floats.next().unwrap_or_default();
+                    Rgb { r: v, g: v, b: v }
+                }
+            };
+        }
+    }
+
+    Ok(Image {
+        width,
+        height,
+        data,
+    })
+}
+
+/// Encodes images in the PFM format. By default, it writes colour images in little-endian byte
+/// order.
+pub struct Encoder<W> {
+    writer: W,
+    channels: Channels,
+    endianness: Endianness,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Construct a new [`Encoder`].
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            channels: Channels::Color,
+            endianness: Endianness::Little,
+        }
+    }
+
+    /// Choose which channels to write. A grayscale file stores the average of the three channels
+    /// of each pixel, or their exact value if they are all equal.
+    pub fn channels(mut self, channels: Channels) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    /// Choose the byte order of the floats.
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    /// Encode an entire [`Image`]. If the length of its data doesn't match its dimensions, an
+    /// error of the kind [`std::io::ErrorKind::InvalidInput`] will be returned.
+    pub fn encode(mut self, image: &Image) -> Result<(), IoError> {
+        let &Image { width, height, .. } = image;
+        if width.checked_mul(height) != Some(image.data.len()) {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "the image data does not match its dimensions",
+            ));
+        }
+
+        let (magic, scale) = match (self.channels, self.endianness) {
+            (Channels::Color, Endianness::Little) => ("PF", "-1.0"),
+            (Channels::Color, Endianness::Big) => ("PF", "1.0"),
+            (Channels::Grayscale, Endianness::Little) => ("Pf", "-1.0"),
+            (Channels::Grayscale, Endianness::Big) => ("Pf", "1.0"),
+        };
+        write!(self.writer, "{}\n{} {}\n{}\n", magic, width, height, scale)?;
+
+        let mut row = Vec::new();
+        for scanline in image.data.chunks_exact(width.max(1)).rev() {
+            row.clear();
+
+            let mut push = |v: f32| {
+                let bytes = match self.endianness {
+                    Endianness::Little => v.to_le_bytes(),
+                    Endianness::Big => v.to_be_bytes(),
+                };
+                row.extend_from_slice(&bytes);
+            };
+
+            for &Rgb { r, g, b } in scanline {
+                match self.channels {
+                    Channels::Color => {
+                        push(r);
+                        push(g);
+                        push(b);
+                    }
+                    Channels::Grayscale if r == g && g == b => push(r),
+                    Channels::Grayscale => push((r + g + b) / 3.),
+                }
+            }
+
+            self.writer.write_all(&row)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read a whitespace separated token from the header, along with the single whitespace character
+/// that ends it.
+fn read_token<R: BufRead>(reader: &mut R) -> Result<Vec<u8>, IoError> {
+    let mut token = Vec::new();
+    loop {
+        let byte = reader.read_byte()?;
+        if byte.is_ascii_whitespace() {
+            if !token.is_empty() {
+                return Ok(token);
+            }
+        } else if token.len() < 32 {
+            token.push(byte);
+        } else {
+            return Err(invalid_data("the PFM header is invalid"));
+        }
+    }
+}
+
+fn parse_token<T: std::str::FromStr, R: BufRead>(reader: &mut R) -> Result<T, IoError> {
+    let token = read_token(reader)?;
+    std::str::from_utf8(&token)
+        .ok()
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| invalid_data("the PFM header is invalid"))
+}
+
+fn invalid_data(message: &'static str) -> IoError {
+    IoError::new(ErrorKind::InvalidData, message)
+}
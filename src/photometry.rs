@@ -0,0 +1,56 @@
+use crate::projection::Projection;
+use crate::Image;
+use std::f64::consts::PI;
+
+impl Image {
+    /// The luminance of every pixel in cd/m², in the same order as [`Image::data`]. See
+    /// [`crate::Rgb::luminance`].
+    pub fn luminance(&self) -> Vec<f32> {
+        self.data.iter().map(|rgb| rgb.luminance()).collect()
+    }
+
+    /// Integrate an equirectangular environment map to the illuminance in lux that it casts on a
+    /// plane facing in the direction of `normal`, using the same direction convention as
+    /// [`Projection`]. Light arriving from behind the plane is ignored. The normal does not need
+    /// to be normalized, but it must not be zero.
+    /// ```rust
+    /// use radiant::{Image, Rgb};
+    ///
+    /// // A uniform sky of 1000 cd/m², with black ground
+    /// let sky = Rgb { r: 1000. / 179., g: 1000. / 179., b: 1000. / 179. };
+    /// let mut data = vec![sky; 64 * 32];
+    /// data[64 * 16..].fill(Rgb::zero());
+    /// let image = Image { width: 64, height: 32, data };
+    ///
+    /// let lux = image.illuminance([0., 1., 0.]);
+    /// assert!((lux - 1000. * std::f32::consts::PI).abs() < 10.);
+    /// ```
+    pub fn illuminance(&self, normal: [f32; 3]) -> f32 {
+        let [x, y, z] = normal;
+        let length = (x * x + y * y + z * z).sqrt();
+        let normal = [x / length, y / length, z / length];
+
+        let (width, height) = (self.width as f64, self.height as f64);
+        let mut illuminance = 0_f64;
+
+        for (row, scanline) in self.data.chunks_exact(self.width.max(1)).enumerate() {
+            let v = (row as f64 + 0.5) / height;
+            // Rows near the poles cover less of the sphere
+            let solid_angle = (2. * PI / width) * (PI / height) * (v * PI).sin();
+
+            for (column, rgb) in scanline.iter().enumerate() {
+                let u = (column as f64 + 0.5) / width;
+                let direction = Projection::Equirectangular
+                    .direction(u as f32, v as f32)
+                    .unwrap_or_default();
+                let cosine: f32 = direction.iter().zip(&normal).map(|(d, n)| d * n).sum();
+
+                if cosine > 0. {
+                    illuminance += (rgb.luminance() * cosine) as f64 * solid_angle;
+                }
+            }
+        }
+
+        illuminance as f32
+    }
+}
@@ -0,0 +1,65 @@
+use crate::tonemap::ToneMap;
+use crate::{Image, Rgb};
+use std::io::{Error as IoError, Write};
+
+impl Image {
+    /// Tone map the image into 8-bit pixels, in the same order as [`Image::data`].
+    pub fn to_rgb8(&self, tone_map: ToneMap) -> Vec<[u8; 3]> {
+        self.data
+            .iter()
+            .map(|&rgb| {
+                let Rgb { r, g, b } = tone_map.apply(rgb);
+                [r, g, b].map(|c| quantize(c, u8::MAX.into()) as u8)
+            })
+            .collect()
+    }
+
+    /// Write the image as a binary 8-bit PPM (`P6`) file, tone mapped with `tone_map`.
+    pub fn write_ppm<W: Write>(&self, mut writer: W, tone_map: ToneMap) -> Result<(), IoError> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(self.to_rgb8(tone_map).as_flattened())
+    }
+
+    /// Write the image as a binary 16-bit PPM (`P6`) file, tone mapped with `tone_map`.
+    pub fn write_ppm16<W: Write>(&self, mut writer: W, tone_map: ToneMap) -> Result<(), IoError> {
+        write!(writer, "P6\n{} {}\n65535\n", self.width, self.height)?;
+        self.write_samples(writer, |rgb| {
+            let Rgb { r, g, b } = tone_map.apply(rgb);
+            [r, g, b]
+        })
+    }
+
+    /// Write the luminance of the image as a binary 16-bit PGM (`P5`) file. The luminance is
+    /// computed from the linear pixels, and then tone mapped with `tone_map`.
+    pub fn write_pgm16<W: Write>(&self, mut writer: W, tone_map: ToneMap) -> Result<(), IoError> {
+        write!(writer, "P5\n{} {}\n65535\n", self.width, self.height)?;
+        self.write_samples(writer, |rgb| {
+            let y = rgb.relative_luminance();
+            [tone_map.apply(Rgb { r: y, g: y, b: y }).g]
+        })
+    }
+
+    /// Write 16-bit samples in big-endian byte order, one scanline at a time.
+    fn write_samples<W: Write, const N: usize>(
+        &self,
+        mut writer: W,
+        samples: impl Fn(Rgb) -> [f32; N],
+    ) -> Result<(), IoError> {
+        let mut row = Vec::new();
+        for scanline in self.data.chunks(self.width.max(1)) {
+            row.clear();
+            for &rgb in scanline {
+                for c in samples(rgb) {
+                    row.extend_from_slice(&quantize(c, u16::MAX.into()).to_be_bytes()[2..]);
+                }
+            }
+            writer.write_all(&row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Round a display value between 0 and 1 to an integer between 0 and `max`.
+fn quantize(c: f32, max: u32) -> u32 {
+    (c * max as f32 + 0.5) as u32
+}
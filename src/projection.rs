@@ -0,0 +1,237 @@
+//! The layouts that environment maps (light probes) are stored in, and conversion between them
+//! with [`Image::reproject`]. Requires the "std" feature.
+
+use crate::{Image, Rgb};
+use std::f32::consts::PI;
+
+/// A layout that an environment map (light probe) can be stored in.
+///
+/// All projections share one direction convention: a right-handed coordinate system where +Y is
+/// up, +X is right and -Z is forward. Image coordinates `u` and `v` go from 0 to 1, left to right
+/// and top to bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Projection {
+    /// A latitude-longitude map. The top row is +Y, the bottom row is -Y, and the center of the
+    /// image looks forward, along -Z. Moving right turns towards +X, and the left and right edges
+    /// meet behind the viewer, at +Z.
+    Equirectangular,
+    /// A photograph of a mirrored sphere, taken from a camera on the +Z axis looking towards -Z.
+    /// The ball fills the inscribed circle of the image, its center reflects +Z (back towards the
+    /// camera) and its rim reflects -Z. Pixels outside the circle are unused.
+    MirrorBall,
+    /// Debevec's angular map. Like [`Projection::MirrorBall`] the center is +Z, but the distance
+    /// from the center is proportional to the angle away from +Z, so the rim of the circle is -Z.
+    /// Pixels outside the circle are unused.
+    AngularMap,
+    /// Six square faces laid out side by side in the order +X, -X, +Y, -Y, +Z, -Z, so the image is
+    /// six times as wide as it is tall. Each face is oriented the way OpenGL expects cube map faces
+    /// to be.
+    Cubemap,
+}
+
+impl Projection {
+    /// Find the unit direction that the image coordinates `u` and `v` represent. Returns [`None`]
+    /// if the coordinates fall outside the part of the image that this projection uses.
+    pub fn direction(self, u: f32, v: f32) -> Option<[f32; 3]> {
+        match self {
+            Self::Equirectangular => {
+                let phi = (u - 0.5) * 2. * PI;
+                let theta = v * PI;
+                let (sin_phi, cos_phi) = phi.sin_cos();
+                let (sin_theta, cos_theta) = theta.sin_cos();
+                Some([sin_theta * sin_phi, cos_theta, -sin_theta * cos_phi])
+            }
+            Self::MirrorBall => {
+                let (s, t) = disk_coordinates(u, v)?;
+                // Reflect a view ray travelling along -Z off the sphere normal
+                let nz = (1. - s * s - t * t).max(0.).sqrt();
+                Some([2. * nz * s, 2. * nz * t, 2. * nz * nz - 1.])
+            }
+            Self::AngularMap => {
+                let (s, t) = disk_coordinates(u, v)?;
+                let r = (s * s + t * t).sqrt();
+                let theta = r * PI;
+                let sin_theta = theta.sin();
+                let (x, y) = if r > 0. {
+                    (s / r * sin_theta, t / r * sin_theta)
+                } else {
+                    (0., 0.)
+                };
+                Some([x, y, theta.cos()])
+            }
+            Self::Cubemap => {
+                if !(0. ..=1.).contains(&u) || !(0. ..=1.).contains(&v) {
+                    return None;
+                }
+
+                let u = u * 6.;
+                let face = (u as usize).min(5);
+                let sc = (u - face as f32) * 2. - 1.;
+                let tc = v * 2. - 1.;
+                let [x, y, z] = match face {
+                    0 => [1., -tc, -sc],
+                    1 => [-1., -tc, sc],
+                    2 => [sc, 1., tc],
+                    3 => [sc, -1., -tc],
+                    4 => [sc, -tc, 1.],
+                    _ => [-sc, -tc, -1.],
+                };
+                Some(normalize([x, y, z]))
+            }
+        }
+    }
+
+    /// Find the image coordinates `u` and `v` that represent a direction. The direction does not
+    /// need to be normalized, but it must not be zero.
+    pub fn coordinates(self, direction: [f32; 3]) -> (f32, f32) {
+        let [x, y, z] = normalize(direction);
+        match self {
+            Self::Equirectangular => {
+                let u = x.atan2(-z) / (2. * PI) + 0.5;
+                let v = y.clamp(-1., 1.).acos() / PI;
+                (u, v)
+            }
+            Self::MirrorBall => {
+                // The sphere normal is halfway between the reflection and the camera direction
+                let [s, t, _] = normalize([x, y, z + 1.]);
+                if s.is_nan() {
+                    // Straight ahead, which is anywhere on the rim
+                    return from_disk(1., 0.);
+                }
+                from_disk(s, t)
+            }
+            Self::AngularMap => {
+                let xy = (x * x + y * y).sqrt();
+                if xy == 0. {
+                    return match z > 0. {
+                        true => from_disk(0., 0.),
+                        false => from_disk(1., 0.),
+                    };
+                }
+                let r = z.clamp(-1., 1.).acos() / PI / xy;
+                from_disk(x * r, y * r)
+            }
+            Self::Cubemap => {
+                let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+                let (face, sc, tc, ma) = if ax >= ay && ax >= az {
+                    match x > 0. {
+                        true => (0, -z, -y, ax),
+                        false => (1, z, -y, ax),
+                    }
+                } else if ay >= az {
+                    match y > 0. {
+                        true => (2, x, z, ay),
+                        false => (3, x, -z, ay),
+                    }
+                } else {
+                    match z > 0. {
+                        true => (4, x, -y, az),
+                        false => (5, -x, -y, az),
+                    }
+                };
+                let s = (sc / ma + 1.) / 2.;
+                let t = (tc / ma + 1.) / 2.;
+                ((face as f32 + s) / 6., t)
+            }
+        }
+    }
+
+    /// The range of source columns that bilinear filtering may blend together around column `x`,
+    /// and whether it wraps around.
+    fn filter_bounds(self, width: usize, x: usize) -> (usize, usize, bool) {
+        match self {
+            Self::Equirectangular => (0, width, true),
+            Self::Cubemap => {
+                let face = (width / 6).max(1);
+                let start = (x / face * face).min(width.saturating_sub(face));
+                (start, start + face, false)
+            }
+            Self::MirrorBall | Self::AngularMap => (0, width, false),
+        }
+    }
+}
+
+impl Image {
+    /// Convert an environment map from one [`Projection`] to another, producing a new image of the
+    /// given size. The source is sampled with bilinear filtering. Pixels of the new image that the
+    /// target projection does not use are left black.
+    pub fn reproject(
+        &self,
+        from: Projection,
+        to: Projection,
+        width: usize,
+        height: usize,
+    ) -> Image {
+        let mut data = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            let v = (y as f32 + 0.5) / height as f32;
+            data.extend((0..width).map(|x| {
+                let u = (x as f32 + 0.5) / width as f32;
+                match to.direction(u, v) {
+                    Some(direction) => {
+                        let (u, v) = from.coordinates(direction);
+                        self.sample(from, u, v)
+                    }
+                    None => Rgb::zero(),
+                }
+            }));
+        }
+
+        Image {
+            width,
+            height,
+            data,
+        }
+    }
+
+    fn sample(&self, projection: Projection, u: f32, v: f32) -> Rgb {
+        if self.width == 0 || self.height == 0 {
+            return Rgb::zero();
+        }
+
+        let x = u * self.width as f32 - 0.5;
+        let y = v * self.height as f32 - 0.5;
+        let center = (u * self.width as f32).max(0.) as usize;
+        let (start, end, wrap) = projection.filter_bounds(self.width, center.min(self.width - 1));
+
+        let column = |x: f32| -> usize {
+            let x = x as isize;
+            match wrap {
+                true => x.rem_euclid(self.width as isize) as usize,
+                false => x.clamp(start as isize, end as isize - 1) as usize,
+            }
+        };
+        let row = |y: f32| (y.max(0.) as usize).min(self.height - 1);
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (left, right) = (column(x0), column(x0 + 1.));
+        let (top, bottom) = (row(y0), row(y0 + 1.));
+
+        let upper = self.pixel(left, top).lerp(*self.pixel(right, top), tx);
+        let lower = self
+            .pixel(left, bottom)
+            .lerp(*self.pixel(right, bottom), tx);
+        upper.lerp(lower, ty)
+    }
+}
+
+/// Map image coordinates to a point on the unit disk, with t pointing up.
+fn disk_coordinates(u: f32, v: f32) -> Option<(f32, f32)> {
+    let s = u * 2. - 1.;
+    let t = 1. - v * 2.;
+    match s * s + t * t <= 1. {
+        true => Some((s, t)),
+        false => None,
+    }
+}
+
+fn from_disk(s: f32, t: f32) -> (f32, f32) {
+    ((s + 1.) / 2., (1. - t) / 2.)
+}
+
+fn normalize([x, y, z]: [f32; 3]) -> [f32; 3] {
+    let length = (x * x + y * y + z * z).sqrt();
+    [x / length, y / length, z / length]
+}
@@ -0,0 +1,85 @@
+//! Global tone mapping, which maps every pixel from linear radiance to a display value between 0
+//! and 1 in the same way. Requires the "std" feature.
+//!
+//! A [`ToneMap`] is what [`crate::Image::to_rgb8`] and the PPM and PGM writers take.
+
+use crate::Rgb;
+
+/// A curve that maps linear radiance values to display values between 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    /// Clip values above 1, and leave the rest linear.
+    Linear,
+    /// Clip values above 1, and apply a gamma of the given value, e.g. 2.2.
+    Gamma(f32),
+    /// Clip values above 1, and apply the sRGB transfer function.
+    Srgb,
+    /// Compress highlights with `x / (1 + x)`, then apply the sRGB transfer function.
+    Reinhard,
+    /// Krzysztof Narkowicz's fit of the ACES filmic curve, followed by the sRGB transfer function.
+    Aces,
+}
+
+/// A global tone mapping operator: an exposure adjustment followed by a [`Curve`].
+/// ```rust
+/// use radiant::tonemap::{Curve, ToneMap};
+/// use radiant::Rgb;
+///
+/// let tone_map = ToneMap { exposure: 1.0, curve: Curve::Linear };
+/// let pixel = tone_map.apply(Rgb { r: 0.25, g: 0.5, b: 1.0 });
+/// assert_eq!(pixel, Rgb { r: 0.5, g: 1.0, b: 1.0 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneMap {
+    /// The exposure adjustment in stops, where each stop doubles the brightness.
+    pub exposure: f32,
+    /// The curve that is applied after the exposure.
+    pub curve: Curve,
+}
+
+impl Default for ToneMap {
+    /// No exposure adjustment, with the [`Curve::Srgb`] curve.
+    fn default() -> Self {
+        Self {
+            exposure: 0.,
+            curve: Curve::Srgb,
+        }
+    }
+}
+
+impl ToneMap {
+    /// Map a linear pixel to display values between 0 and 1. Negative and NaN channels become 0.
+    pub fn apply(&self, rgb: Rgb) -> Rgb {
+        let scale = self.exposure.exp2();
+        let map = |c: f32| {
+            let c = c * scale;
+            let c = if c > 0. { c } else { 0. };
+            let mapped = match self.curve {
+                Curve::Linear => c.min(1.),
+                Curve::Gamma(gamma) => c.min(1.).powf(gamma.recip()),
+                Curve::Srgb => srgb(c.min(1.)),
+                Curve::Reinhard => srgb(1. - (1. + c).recip()),
+                Curve::Aces => {
+                    let aces = (c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14);
+                    srgb(aces.min(1.))
+                }
+            };
+            mapped.clamp(0., 1.)
+        };
+
+        Rgb {
+            r: map(rgb.r),
+            g: map(rgb.g),
+            b: map(rgb.b),
+        }
+    }
+}
+
+/// The sRGB transfer function, for a linear value between 0 and 1.
+fn srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1. / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{Image, Rgb};
+use alloc::vec::Vec;
+
+impl Image {
+    /// Copy out a rectangular region of the image, starting at the given x and y coordinate. Will
+    /// panic if the region does not fit within the image.
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Image {
+        assert!(
+            matches!(x.checked_add(width), Some(right) if right <= self.width)
+                && matches!(y.checked_add(height), Some(bottom) if bottom <= self.height),
+            "crop region out of bounds"
+        );
+
+        let mut data = Vec::with_capacity(width * height);
+        for row in y..y + height {
+            let start = self.pixel_offset(x, row);
+            data.extend_from_slice(&self.data[start..start + width]);
+        }
+
+        Image {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Mirror the image in place, so that the left edge becomes the right edge.
+    pub fn flip_horizontal(&mut self) {
+        if self.width == 0 {
+            return;
+        }
+
+        self.data
+            .chunks_exact_mut(self.width)
+            .for_each(|row| row.reverse());
+    }
+
+    /// Mirror the image in place, so that the top edge becomes the bottom edge.
+    pub fn flip_vertical(&mut self) {
+        let width = self.width;
+        for y in 0..self.height / 2 {
+            let (top, bottom) = self.data.split_at_mut((self.height - 1 - y) * width);
+            top[y * width..(y + 1) * width].swap_with_slice(&mut bottom[..width]);
+        }
+    }
+
+    /// Rotate the image 90 degrees clockwise, returning a new image with the width and height
+    /// swapped.
+    pub fn rotate_90(&self) -> Image {
+        self.transpose_with(|image, x, y| image.pixel(y, image.height - 1 - x))
+    }
+
+    /// Rotate the image 180 degrees in place.
+    pub fn rotate_180(&mut self) {
+        self.data.reverse();
+    }
+
+    /// Rotate the image 90 degrees counter-clockwise, returning a new image with the width and
+    /// height swapped.
+    pub fn rotate_270(&self) -> Image {
+        self.transpose_with(|image, x, y| image.pixel(image.width - 1 - y, x))
+    }
+
+    /// Rotate an equirectangular environment map about its vertical (up) axis by `angle` radians.
+    ///
+    /// This is a horizontal shift that wraps around the edges. A positive angle moves the contents
+    /// of the image to the right, so the pixel at column `x` ends up at column
+    /// `x + angle / (2 * PI) * width`. Shifts that are not a whole number of pixels are resampled
+    /// with linear interpolation. Requires the "std" feature.
+    #[cfg(feature = "std")]
+    pub fn rotate_yaw(&self, angle: f32) -> Image {
+        let width = self.width;
+        let mut data = Vec::with_capacity(self.data.len());
+
+        if width != 0 {
+            let shift = (angle / core::f32::consts::TAU * width as f32).rem_euclid(width as f32);
+            let whole = shift.floor();
+            let t = shift - whole;
+            // A shift that rounds up to the width is the same as no shift at all
+            let whole = whole as usize % width;
+
+            for row in self.data.chunks_exact(width) {
+                data.extend((0..width).map(|x| {
+                    // Destination x samples between source columns x - whole - 1 and x - whole
+                    let right = (x + width - whole) % width;
+                    let left = (right + width - 1) % width;
+                    row[right].lerp(row[left], t)
+                }));
+            }
+        }
+
+        Image {
+            width,
+            height: self.height,
+            data,
+        }
+    }
+
+    /// Stretch an image with non-square pixels so that its pixels become square, given the ratio
+    /// of the height of a pixel to its width, e.g. from [`crate::Header::pixel_aspect`].
+    ///
+    /// The image is only ever enlarged: tall pixels make it taller, and wide pixels make it wider,
+    /// resampling the stretched axis with linear interpolation. The ratio is clamped to between
+    /// 1/16 and 16, so that an extreme `PIXASPECT` in an untrusted header can't make the image
+    /// arbitrarily large. Requires the "std" feature.
+    #[cfg(feature = "std")]
+    pub fn resample_square_pixels(&self, pixel_aspect: f32) -> Image {
+        const MAX_STRETCH: f32 = 16.;
+
+        let (width, height) = (self.width, self.height);
+        let stretched = |length: usize, factor: f32| (length as f32 * factor).round() as usize;
+
+        if !(pixel_aspect > 0. && pixel_aspect.is_finite()) || pixel_aspect == 1. {
+            return self.clone();
+        }
+        let pixel_aspect = pixel_aspect.clamp(MAX_STRETCH.recip(), MAX_STRETCH);
+
+        if pixel_aspect == 1. {
+            self.clone()
+        } else if pixel_aspect > 1. {
+            let new_height = stretched(height, pixel_aspect);
+            let mut data = Vec::with_capacity(width * new_height);
+            for y in 0..new_height {
+                let (top, bottom, t) = linear_taps(y, height, new_height);
+                let (top, bottom) = (top * width, bottom * width);
+                data.extend((0..width).map(|x| self.data[top + x].lerp(self.data[bottom + x], t)));
+            }
+            Image {
+                width,
+                height: new_height,
+                data,
+            }
+        } else {
+            let new_width = stretched(width, pixel_aspect.recip());
+            let mut data = Vec::with_capacity(new_width * height);
+            let taps: Vec<_> = (0..new_width)
+                .map(|x| linear_taps(x, width, new_width))
+                .collect();
+            for row in self.data.chunks_exact(width.max(1)) {
+                data.extend(
+                    taps.iter()
+                        .map(|&(left, right, t)| row[left].lerp(row[right], t)),
+                );
+            }
+            Image {
+                width: new_width,
+                height,
+                data,
+            }
+        }
+    }
+
+    fn transpose_with<'a>(&'a self, source: impl Fn(&'a Image, usize, usize) -> &'a Rgb) -> Image {
+        let (width, height) = (self.height, self.width);
+        let mut data = Vec::with_capacity(self.data.len());
+
+        for y in 0..height {
+            data.extend((0..width).map(|x| *source(self, x, y)));
+        }
+
+        Image {
+            width,
+            height,
+            data,
+        }
+    }
+}
+
+/// The two source pixels and the weight of the second one, for linearly resampling the pixel at
+/// `index` of `new_length` from `length` pixels, lining up their centers.
+#[cfg(feature = "std")]
+fn linear_taps(index: usize, length: usize, new_length: usize) -> (usize, usize, f32) {
+    let position = (index as f32 + 0.5) * length as f32 / new_length as f32 - 0.5;
+    let position = position.clamp(0., length.saturating_sub(1) as f32);
+    let first = position.floor() as usize;
+    let second = (first + 1).min(length.saturating_sub(1));
+    (first, second, position - first as f32)
+}
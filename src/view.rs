@@ -0,0 +1,292 @@
+//! Cameras, as stored in the `VIEW` variable of images rendered by Radiance. Get one from
+//! [`Header::view`]. Requires the "std" feature.
+
+use crate::Header;
+use std::f32::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+/// The projection of a [`View`], given by its `-vt` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewType {
+    /// A perspective view (`-vtv`), the default.
+    Perspective,
+    /// A parallel (orthographic) view (`-vtl`), where the view sizes are in world units.
+    Parallel,
+    /// A cylindrical panorama (`-vtc`): perspective vertically, angular horizontally.
+    Cylindrical,
+    /// A hemispherical fisheye (`-vth`), the projection of the view hemisphere onto a plane.
+    Hemispherical,
+    /// An angular fisheye (`-vta`), where the distance from the center is proportional to the
+    /// angle away from the view direction.
+    Angular,
+    /// A stereographic fisheye, which Radiance calls a planisphere (`-vts`).
+    Fisheye,
+}
+
+/// A camera, as stored in the `VIEW` variable of images rendered by Radiance's `rpict`, e.g.
+/// `VIEW= -vtv -vp 0 0 1.5 -vd 0 1 0 -vu 0 0 1 -vh 60 -vv 45`. Requires the "std" feature.
+///
+/// Unlike [`crate::projection::Projection`], views follow Radiance's convention that +Z is up by default.
+/// ```rust
+/// use radiant::view::{View, ViewType};
+///
+/// let view: View = "-vtv -vp 0 0 1.5 -vd 0 1 0 -vh 90 -vv 90".parse().expect("invalid view");
+/// assert_eq!(view.view_type, ViewType::Perspective);
+///
+/// // The ray through the middle of the image goes straight ahead
+/// let ray = view.ray(0.5, 0.5).expect("no ray");
+/// assert_eq!(ray.origin, [0., 0., 1.5]);
+/// assert_eq!(ray.direction, [0., 1., 0.]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    /// The projection (`-vt`).
+    pub view_type: ViewType,
+    /// The position of the eye (`-vp`).
+    pub position: [f32; 3],
+    /// The view direction (`-vd`), which does not need to be normalized.
+    pub direction: [f32; 3],
+    /// The up direction (`-vu`), which does not need to be normalized.
+    pub up: [f32; 3],
+    /// The horizontal field of view in degrees, or the width of the view in world units for
+    /// [`ViewType::Parallel`] (`-vh`).
+    pub horizontal: f32,
+    /// The vertical field of view in degrees, or the height of the view in world units for
+    /// [`ViewType::Parallel`] (`-vv`).
+    pub vertical: f32,
+    /// The distance from the eye to the fore clipping plane, where rays start (`-vo`).
+    pub fore_clip: f32,
+    /// The distance from the eye to the aft clipping plane, or 0 for none (`-va`).
+    pub aft_clip: f32,
+    /// How far the image is shifted to the right, as a fraction of its width (`-vs`).
+    pub shift: f32,
+    /// How far the image is lifted up, as a fraction of its height (`-vl`).
+    pub lift: f32,
+}
+
+/// A ray in world space, from a [`View`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    /// Where the ray starts.
+    pub origin: [f32; 3],
+    /// The unit direction of the ray.
+    pub direction: [f32; 3],
+}
+
+/// The error returned when a [`View`] can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseViewError {
+    message: &'static str,
+}
+
+impl fmt::Display for ParseViewError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.message)
+    }
+}
+
+impl std::error::Error for ParseViewError {}
+
+impl Default for View {
+    /// Radiance's default view: `-vtv -vp 0 0 0 -vd 0 1 0 -vu 0 0 1 -vh 45 -vv 45`.
+    fn default() -> Self {
+        Self {
+            view_type: ViewType::Perspective,
+            position: [0., 0., 0.],
+            direction: [0., 1., 0.],
+            up: [0., 0., 1.],
+            horizontal: 45.,
+            vertical: 45.,
+            fore_clip: 0.,
+            aft_clip: 0.,
+            shift: 0.,
+            lift: 0.,
+        }
+    }
+}
+
+impl FromStr for View {
+    type Err = ParseViewError;
+
+    /// Parse view options on top of the [default view](View::default).
+    fn from_str(options: &str) -> Result<Self, Self::Err> {
+        let mut view = Self::default();
+        view.apply_options(options)?;
+        Ok(view)
+    }
+}
+
+impl View {
+    /// Apply view options to this view, the way later options override earlier ones in Radiance.
+    /// Tokens that aren't view options, such as the name of the program that wrote them, are
+    /// ignored.
+    pub fn apply_options(&mut self, options: &str) -> Result<(), ParseViewError> {
+        let invalid = |message| ParseViewError { message };
+        let mut tokens = options.split_whitespace();
+
+        let number = |tokens: &mut std::str::SplitWhitespace| -> Result<f32, ParseViewError> {
+            tokens
+                .next()
+                .and_then(|token| token.parse().ok())
+                .ok_or_else(|| invalid("a view option is missing a number"))
+        };
+
+        while let Some(token) = tokens.next() {
+            match token {
+                "-vp" | "-vd" | "-vu" => {
+                    let vector = [
+                        number(&mut tokens)?,
+                        number(&mut tokens)?,
+                        number(&mut tokens)?,
+                    ];
+                    match token {
+                        "-vp" => self.position = vector,
+                        "-vd" => self.direction = vector,
+                        _ => self.up = vector,
+                    }
+                }
+                "-vh" => self.horizontal = number(&mut tokens)?,
+                "-vv" => self.vertical = number(&mut tokens)?,
+                "-vo" => self.fore_clip = number(&mut tokens)?,
+                "-va" => self.aft_clip = number(&mut tokens)?,
+                "-vs" => self.shift = number(&mut tokens)?,
+                "-vl" => self.lift = number(&mut tokens)?,
+                _ if token.starts_with("-vt") => {
+                    self.view_type = match &token[3..] {
+                        "v" => ViewType::Perspective,
+                        "l" => ViewType::Parallel,
+                        "c" => ViewType::Cylindrical,
+                        "h" => ViewType::Hemispherical,
+                        "a" => ViewType::Angular,
+                        "s" => ViewType::Fisheye,
+                        _ => return Err(invalid("unknown view type")),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The ray through the image coordinates `u` and `v`, which go from 0 to 1, left to right and
+    /// top to bottom. Returns [`None`] for the corners of fisheye views that see nothing, and for
+    /// views whose direction is zero or parallel to their up vector.
+    pub fn ray(&self, u: f32, v: f32) -> Option<Ray> {
+        let direction = normalize(self.direction)?;
+        let right = normalize(cross(direction, self.up))?;
+        let up = cross(right, direction);
+
+        // Centered coordinates, with y going up like in Radiance
+        let x = u + self.shift - 0.5;
+        let y = 0.5 - v + self.lift;
+        let combine = |a: f32, b: f32, c: f32| -> [f32; 3] {
+            [0, 1, 2].map(|i| a * right[i] + b * up[i] + c * direction[i])
+        };
+
+        let half_angle = |degrees: f32| degrees.to_radians() / 2.;
+        let ray_direction = match self.view_type {
+            ViewType::Perspective => {
+                let x = x * 2. * half_angle(self.horizontal).tan();
+                let y = y * 2. * half_angle(self.vertical).tan();
+                normalize(combine(x, y, 1.))?
+            }
+            ViewType::Parallel => {
+                let origin = add(
+                    self.position,
+                    combine(x * self.horizontal, y * self.vertical, self.fore_clip),
+                );
+                return Some(Ray { origin, direction });
+            }
+            ViewType::Cylindrical => {
+                let angle = x * self.horizontal.to_radians();
+                let y = y * 2. * half_angle(self.vertical).tan();
+                normalize(combine(angle.sin(), y, angle.cos()))?
+            }
+            ViewType::Hemispherical => {
+                let x = x * 2. * half_angle(self.horizontal).sin();
+                let y = y * 2. * half_angle(self.vertical).sin();
+                let z = 1. - x * x - y * y;
+                if z < 0. {
+                    return None;
+                }
+                combine(x, y, z.sqrt())
+            }
+            ViewType::Angular | ViewType::Fisheye => {
+                let (x, y, theta) = if self.view_type == ViewType::Angular {
+                    let (x, y) = (x * self.horizontal / 180., y * self.vertical / 180.);
+                    (x, y, PI * (x * x + y * y).sqrt())
+                } else {
+                    // Stereographic, where the distance from the center is tan(theta / 2)
+                    let x = x * 2. * (self.horizontal.to_radians() / 4.).tan();
+                    let y = y * 2. * (self.vertical.to_radians() / 4.).tan();
+                    (x, y, 2. * (x * x + y * y).sqrt().atan())
+                };
+                if theta > PI {
+                    return None;
+                }
+
+                let r = (x * x + y * y).sqrt();
+                let (s, t) = if r > 0. { (x / r, y / r) } else { (0., 0.) };
+                let (sin_theta, cos_theta) = theta.sin_cos();
+                combine(s * sin_theta, t * sin_theta, cos_theta)
+            }
+        };
+
+        let origin = add(self.position, ray_direction.map(|c| c * self.fore_clip));
+        Some(Ray {
+            origin,
+            direction: ray_direction,
+        })
+    }
+
+    /// The ray through the center of pixel `(x, y)` of a `width` by `height` image, counting from
+    /// the top left. See [`View::ray`].
+    pub fn pixel_ray(&self, x: usize, y: usize, width: usize, height: usize) -> Option<Ray> {
+        let u = (x as f32 + 0.5) / width as f32;
+        let v = (y as f32 + 0.5) / height as f32;
+        self.ray(u, v)
+    }
+}
+
+impl Header {
+    /// The camera that the image was rendered with, from applying every `VIEW` variable in order.
+    /// Returns [`None`] if there are none, or if any of them can't be parsed. Requires the "std"
+    /// feature.
+    pub fn view(&self) -> Option<View> {
+        let mut views = self
+            .variables
+            .iter()
+            .filter(|(name, _)| name == "VIEW")
+            .peekable();
+        views.peek()?;
+
+        let mut view = View::default();
+        for (_, options) in views {
+            view.apply_options(options).ok()?;
+        }
+        Some(view)
+    }
+}
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize([x, y, z]: [f32; 3]) -> Option<[f32; 3]> {
+    let length = (x * x + y * y + z * z).sqrt();
+    if length > 0. {
+        Some([x / length, y / length, z / length])
+    } else {
+        None
+    }
+}
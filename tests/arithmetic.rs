@@ -0,0 +1,90 @@
+use radiant::{Image, Rgb};
+
+fn rgb(r: f32, g: f32, b: f32) -> Rgb {
+    Rgb { r, g, b }
+}
+
+fn image(data: Vec<Rgb>) -> Image {
+    Image {
+        width: data.len(),
+        height: 1,
+        data,
+    }
+}
+
+#[test]
+fn rgb_operators() {
+    let a = rgb(1., 2., 3.);
+    let b = rgb(0.5, 4., -1.);
+
+    assert_eq!(a + b, rgb(1.5, 6., 2.));
+    assert_eq!(a - b, rgb(0.5, -2., 4.));
+    assert_eq!(a * b, rgb(0.5, 8., -3.));
+    assert_eq!(a / b, rgb(2., 0.5, -3.));
+    assert_eq!(a * 2., rgb(2., 4., 6.));
+    assert_eq!(2. * a, rgb(2., 4., 6.));
+    assert_eq!(a / 2., rgb(0.5, 1., 1.5));
+    assert_eq!(-a, rgb(-1., -2., -3.));
+    assert_eq!(a.lerp(b, 0.5), rgb(0.75, 3., 1.));
+
+    let mut c = a;
+    c += b;
+    c *= 2.;
+    c -= a;
+    c /= rgb(1., 2., 4.);
+    assert_eq!(c, rgb(2., 5., 0.25));
+}
+
+#[test]
+fn rgb_sum() {
+    let pixels = [rgb(1., 2., 3.), rgb(4., 5., 6.)];
+    assert_eq!(pixels.iter().sum::<Rgb>(), rgb(5., 7., 9.));
+    assert_eq!(pixels.iter().copied().sum::<Rgb>(), rgb(5., 7., 9.));
+    assert_eq!(std::iter::empty::<Rgb>().sum::<Rgb>(), Rgb::zero());
+}
+
+#[test]
+fn image_arithmetic() {
+    let mut a = image(vec![rgb(1., 2., 3.), rgb(4., 5., 6.)]);
+    let b = image(vec![rgb(1., 1., 1.), rgb(0., 2., 0.5)]);
+
+    a.scale(2.);
+    assert_eq!(a.data, [rgb(2., 4., 6.), rgb(8., 10., 12.)]);
+    a.add(&b);
+    assert_eq!(a.data, [rgb(3., 5., 7.), rgb(8., 12., 12.5)]);
+    a.multiply(&b);
+    assert_eq!(a.data, [rgb(3., 5., 7.), rgb(0., 24., 6.25)]);
+    a.lerp(&b, 1.);
+    assert_eq!(a.data, b.data);
+
+    // A pcomb-like expression: the green channel of one image over the red channel of the other
+    a.zip_map(&b, |x, y| rgb(x.g / y.r, 0., 0.));
+    assert_eq!(a.data[0], rgb(1., 0., 0.));
+}
+
+#[test]
+fn image_clamp_and_replace() {
+    let mut a = image(vec![
+        rgb(-1., 0.5, 2.),
+        rgb(f32::NAN, f32::INFINITY, f32::NEG_INFINITY),
+    ]);
+
+    a.replace_non_finite(0.);
+    assert_eq!(a.data[1], Rgb::zero());
+    a.clamp(0., 1.);
+    assert_eq!(a.data[0], rgb(0., 0.5, 1.));
+}
+
+#[test]
+#[should_panic(expected = "the images must have the same dimensions")]
+fn image_mismatched_dimensions() {
+    let mut a = image(vec![Rgb::zero(); 2]);
+    a.add(&image(vec![Rgb::zero(); 3]));
+}
+
+#[test]
+#[should_panic(expected = "the minimum must not be greater than the maximum")]
+fn image_clamp_invalid_bounds() {
+    let mut a = image(Vec::new());
+    a.clamp(0., f32::NAN);
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "async")]
+
+use futures_executor::block_on;
+use futures_util::io::{AsyncBufRead, AsyncRead};
+use radiant::{AsyncLoader, Rgb};
+use std::io::ErrorKind;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// An in-memory reader that hands out data a few bytes at a time, in varying amounts, and
+/// pretends not to be ready every other time it is polled.
+struct Trickle<'a> {
+    data: &'a [u8],
+    chunk: usize,
+    offered: usize,
+    ready: bool,
+}
+
+impl<'a> Trickle<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            chunk: 0,
+            offered: 0,
+            ready: false,
+        }
+    }
+}
+
+impl AsyncRead for Trickle<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let chunk = futures_util::ready!(self.as_mut().poll_fill_buf(cx))?;
+        let len = chunk.len().min(buf.len());
+        buf[..len].copy_from_slice(&chunk[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl AsyncBufRead for Trickle<'_> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+        this.ready = !this.ready;
+        if !this.ready {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        if this.offered == 0 {
+            this.chunk = this.chunk % 5 + 1;
+            this.offered = this.chunk.min(this.data.len());
+        }
+        Poll::Ready(Ok(&this.data[..this.offered]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.data = &this.data[amt..];
+        this.offered -= amt;
+    }
+}
+
+fn new_rle_image(width: usize, height: usize) -> Vec<u8> {
+    let mut file = format!(
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        height, width
+    )
+    .into_bytes();
+    for y in 0..height {
+        file.extend_from_slice(&[2, 2, (width >> 8) as u8, width as u8]);
+        for chunk in (0..width).collect::<Vec<_>>().chunks(128) {
+            file.push(chunk.len() as u8);
+            file.extend(chunk.iter().map(|&x| (x * 3 + y) as u8));
+        }
+        for &value in &[y as u8, 9, 129] {
+            let mut left = width;
+            while left > 0 {
+                let count = left.min(127);
+                file.extend_from_slice(&[128 | count as u8, value]);
+                left -= count;
+            }
+        }
+    }
+    file
+}
+
+#[test]
+fn async_matches_sync() {
+    let file = new_rle_image(150, 3);
+    let expected = radiant::load(&file[..]).unwrap();
+
+    let image = block_on(async {
+        AsyncLoader::new(Trickle::new(&file))
+            .await?
+            .load_image()
+            .await
+    })
+    .unwrap();
+    assert_eq!((image.width, image.height), (150, 3));
+    assert_eq!(image.data, expected.data);
+}
+
+#[test]
+fn async_from_one_buffer() {
+    let file = new_rle_image(2000, 40);
+    let expected = radiant::load(&file[..]).unwrap();
+
+    // Every scanline is decoded straight from the reader's buffer
+    let reader = futures_util::io::Cursor::new(&file[..]);
+    let image = block_on(async { AsyncLoader::new(reader).await?.load_image().await }).unwrap();
+    assert_eq!((image.width, image.height), (2000, 40));
+    assert_eq!(image.data, expected.data);
+}
+
+#[test]
+fn async_scanlines_old_rle() {
+    let file = b"#?RADIANCE\0\n\n-Y 2 +X 2\n\
+                 \xff\x00\xff\x80\x01\x01\x01\x01\
+                 \x00\xff\x00\x80\x01\x01\x01\x01";
+    let expected = radiant::load(&file[..]).unwrap();
+
+    let mut scanlines = block_on(AsyncLoader::new(Trickle::new(file)))
+        .unwrap()
+        .scanlines();
+    let mut buffer = [Rgb::zero(); 2];
+    for row in expected.data.chunks(2) {
+        block_on(scanlines.read_scanline(&mut buffer)).unwrap();
+        assert_eq!(&buffer, row);
+    }
+}
+
+#[test]
+fn async_errors() {
+    let file = new_rle_image(16, 2);
+    let truncated = &file[..file.len() - 3];
+    let error = block_on(async {
+        AsyncLoader::new(Trickle::new(truncated))
+            .await?
+            .load_image()
+            .await
+    })
+    .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+    let error = block_on(AsyncLoader::new(Trickle::new(b"#?RADIANZE\n\n"))).err();
+    assert_eq!(error.unwrap().kind(), ErrorKind::InvalidData);
+}
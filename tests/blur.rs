@@ -0,0 +1,99 @@
+#![cfg(feature = "std")]
+
+use radiant::blur::{Bloom, Edges};
+use radiant::{Image, Rgb};
+
+/// A black image with a single bright pixel.
+fn impulse(width: usize, height: usize, x: usize, y: usize, value: f32) -> Image {
+    let mut data = vec![Rgb::zero(); width * height];
+    data[y * width + x] = Rgb {
+        r: value,
+        g: value,
+        b: value,
+    };
+    Image {
+        width,
+        height,
+        data,
+    }
+}
+
+fn total(image: &Image) -> f32 {
+    image.data.iter().map(|rgb| rgb.g).sum()
+}
+
+#[test]
+fn blur_impulse() {
+    let blurred = impulse(15, 15, 7, 7, 1.).gaussian_blur(1.5, Edges::Clamp);
+    assert!((total(&blurred) - 1.).abs() < 1e-5);
+
+    // The result is symmetric, and falls off away from the center
+    let at = |x, y| blurred.pixel(x, y).g;
+    assert_eq!(at(5, 7), at(9, 7));
+    assert_eq!(at(7, 5), at(7, 9));
+    assert!((at(5, 7) - at(7, 5)).abs() < 1e-7);
+    assert!(at(7, 7) > at(8, 7) && at(8, 7) > at(9, 7) && at(9, 7) > 0.);
+
+    // A constant image stays constant, whatever the edges
+    let mut constant = impulse(4, 3, 0, 0, 0.);
+    constant.map(|_| Rgb {
+        r: 2.,
+        g: 2.,
+        b: 2.,
+    });
+    for edges in [Edges::Clamp, Edges::Wrap, Edges::Equirectangular] {
+        let blurred = constant.gaussian_blur(3., edges);
+        assert!(blurred.data.iter().all(|rgb| (rgb.g - 2.).abs() < 1e-5));
+    }
+}
+
+#[test]
+fn blur_equirectangular_wrap() {
+    let image = impulse(16, 8, 0, 0, 1.);
+
+    let clamped = image.gaussian_blur(1., Edges::Clamp);
+    assert_eq!(clamped.pixel(15, 0).g, 0.);
+
+    // Equirectangular maps wrap around horizontally, but not vertically
+    let wrapped = image.gaussian_blur(1., Edges::Equirectangular);
+    assert_eq!(wrapped.pixel(15, 0).g, wrapped.pixel(1, 0).g);
+    assert_eq!(wrapped.pixel(0, 7).g, 0.);
+
+    let tiled = image.gaussian_blur(1., Edges::Wrap);
+    assert_eq!(tiled.pixel(0, 7).g, tiled.pixel(0, 1).g);
+}
+
+#[test]
+fn bloom_conserves_energy() {
+    let image = impulse(32, 32, 16, 16, 100.);
+    let bloom = Bloom {
+        threshold: 179.,
+        intensity: 0.2,
+        ..Bloom::default()
+    };
+
+    // Light can't scatter past the edges of a tiling image, so none of it is lost
+    let glared = bloom.apply(&image, Edges::Wrap);
+    assert!((total(&glared) - total(&image)).abs() < 1e-3);
+    assert!(glared.pixel(16, 16).g < 100.);
+    assert!(glared.pixel(24, 16).g > 0.);
+
+    // Nothing above the threshold means nothing changes
+    let dim = impulse(32, 32, 16, 16, 0.5);
+    assert_eq!(bloom.apply(&dim, Edges::Clamp).data, dim.data);
+}
+
+#[test]
+fn bloom_negative_threshold() {
+    let image = impulse(16, 16, 8, 8, 10.);
+    let bloom = Bloom {
+        threshold: -1.,
+        intensity: 0.5,
+        ..Bloom::default()
+    };
+
+    // Black pixels have nothing to scatter, rather than turning into NaN
+    let glared = bloom.apply(&image, Edges::Wrap);
+    assert!(glared.data.iter().all(|rgb| rgb.g.is_finite()));
+    assert!((total(&glared) - total(&image)).abs() < 1e-3);
+}
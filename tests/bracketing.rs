@@ -0,0 +1,93 @@
+#![cfg(feature = "std")]
+
+use radiant::bracketing::{Exposure, Response};
+
+const TIMES: [f32; 4] = [1. / 16., 0.25, 1., 4.];
+
+/// A scene whose radiance grows exponentially from left to right, spanning 9 stops.
+fn radiance(i: usize) -> f32 {
+    (i as f32 / 1024. * 9. - 6.).exp2()
+}
+
+/// Photograph the scene with a camera that has a gamma curve, like most 8-bit images.
+fn photograph(time: f32, response: impl Fn(f32) -> f32) -> Exposure {
+    let data = (0..1024)
+        .map(|i| {
+            let z = (response(radiance(i) * time).clamp(0., 1.) * 255.).round() as u8;
+            [z, z, z]
+        })
+        .collect();
+
+    Exposure {
+        width: 64,
+        height: 16,
+        data,
+        time,
+    }
+}
+
+/// Check the merged image against the scene, up to an unknown scale given by its middle pixel.
+fn assert_relative(image: &radiant::Image, tolerance: f32) {
+    let scale = radiance(512) / image.data[512].g;
+    for (i, rgb) in image.data.iter().enumerate() {
+        let error = (rgb.g * scale / radiance(i) - 1.).abs();
+        assert!(error < tolerance, "pixel {} is off by {}", i, error);
+    }
+}
+
+#[test]
+fn merge_linear() {
+    let exposures = TIMES.map(|time| photograph(time, |x| x));
+    let image = Response::linear().merge(&exposures);
+    assert_eq!((image.width, image.height), (64, 16));
+    assert_relative(&image, 0.05);
+}
+
+#[test]
+fn recover_gamma_response() {
+    let gamma = |x: f32| x.powf(1. / 2.2);
+    let exposures = TIMES.map(|time| photograph(time, gamma));
+
+    let response = Response::recover(&exposures, 20.);
+    let curve = &response.curves[1];
+
+    // The curve should follow 2.2 * ln(z) in the well exposed part of the range
+    for z in [32, 64, 192] {
+        let expected = 2.2 * (z as f32 / 128.).ln();
+        assert!(
+            (curve[z] - curve[128] - expected).abs() < 0.1,
+            "g({}) = {}, expected {}",
+            z,
+            curve[z] - curve[128],
+            expected
+        );
+    }
+    assert!(curve.windows(2).skip(8).take(240).all(|w| w[0] < w[1]));
+
+    assert_relative(&response.merge(&exposures), 0.1);
+}
+
+#[test]
+fn merge_clipped_pixels() {
+    // Every exposure is white, or every exposure is black
+    let exposure = |z: u8, time| Exposure {
+        width: 1,
+        height: 1,
+        data: vec![[z; 3]],
+        time,
+    };
+
+    let white = Response::linear().merge(&[exposure(255, 1.), exposure(255, 0.5)]);
+    assert!((white.data[0].r - 255. / 0.5).abs() < 1e-3);
+
+    let black = Response::linear().merge(&[exposure(0, 1.), exposure(0, 0.5)]);
+    assert!((black.data[0].r - 0.5).abs() < 1e-6);
+}
+
+#[test]
+#[should_panic(expected = "the exposures must have the same dimensions")]
+fn merge_mismatched_dimensions() {
+    let mut exposures = TIMES.map(|time| photograph(time, |x| x));
+    exposures[2].width = 32;
+    Response::linear().merge(&exposures);
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/gradient.hdr");
+
+fn radiant(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_radiant"))
+        .args(args)
+        .output()
+        .expect("failed to run radiant")
+}
+
+/// A fresh directory for the files a test writes.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("radiant-cli-{}-{}", std::process::id(), name));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn cli_info() {
+    let output = radiant(&["info", FIXTURE]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("dimensions:  16 x 8"));
+    assert!(stdout.contains("format:      32-bit_rle_rgbe"));
+    assert!(stdout.contains("  EXPOSURE=1"));
+    assert!(stdout.contains("  # a small gradient for the command line tests"));
+    assert!(stdout.contains("log average:"));
+}
+
+#[test]
+fn cli_info_exposure() {
+    let mean = |output: Output| -> f64 {
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let line = stdout.lines().find(|l| l.contains("mean:")).unwrap();
+        line.split_whitespace().last().unwrap().parse().unwrap()
+    };
+
+    // An image that has been brightened by EXPOSURE=2 has the same luminance as the original
+    let dir = temp_dir("exposure");
+    let exposed = dir.join("exposed.hdr");
+    let mut image = radiant::load(&fs::read(FIXTURE).unwrap()[..]).unwrap();
+    image.data.iter_mut().for_each(|rgb| *rgb *= 2.);
+    let header = radiant::Header {
+        variables: vec![("EXPOSURE".into(), "2".into())],
+        other: Vec::new(),
+    };
+    let mut file = Vec::new();
+    radiant::Encoder::new(&mut file)
+        .header(header)
+        .encode(&image)
+        .unwrap();
+    fs::write(&exposed, file).unwrap();
+
+    let output = radiant(&["info", exposed.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("divided by EXPOSURE=2"));
+    let expected = mean(radiant(&["info", FIXTURE]));
+    assert!((mean(output) - expected).abs() <= expected * 1e-3);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cli_validate() {
+    let output = radiant(&["validate", FIXTURE]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout, "ok: 16 x 8, all 8 scanlines decoded\n");
+
+    // Cut off the last two scanlines
+    let dir = temp_dir("validate");
+    let truncated = dir.join("truncated.hdr");
+    let file = fs::read(FIXTURE).unwrap();
+    fs::write(&truncated, &file[..file.len() - 60]).unwrap();
+
+    let output = radiant(&["validate", truncated.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("error: "));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("2 of 8 scanlines could not be decoded"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cli_convert() {
+    let expected = radiant::load(&fs::read(FIXTURE).unwrap()[..]).unwrap();
+    let dir = temp_dir("convert");
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_owned();
+
+    // Through .pfm and back to a flat .hdr, which keeps the pixels to within the precision of RGBE
+    assert!(radiant(&["convert", FIXTURE, &path("image.pfm")])
+        .status
+        .success());
+    assert!(
+        radiant(&["convert", &path("image.pfm"), &path("image.hdr"), "--flat"])
+            .status
+            .success()
+    );
+    // From .hdr to .hdr, the header variables are kept
+    assert!(radiant(&["convert", FIXTURE, &path("copy.hdr")])
+        .status
+        .success());
+    let copy = fs::read(path("copy.hdr")).unwrap();
+    let loader = radiant::Loader::new(&copy[..]).unwrap();
+    assert_eq!(loader.header.get("EXPOSURE"), Some("1"));
+    assert_eq!(
+        loader.header.other,
+        ["# a small gradient for the command line tests"]
+    );
+
+    let round_trip = radiant::load(&fs::read(path("image.hdr")).unwrap()[..]).unwrap();
+    assert_eq!((round_trip.width, round_trip.height), (16, 8));
+    for (a, b) in round_trip.data.iter().zip(&expected.data) {
+        // The channels of a pixel share an exponent, so the error depends on the largest one
+        let tolerance = b.r.max(b.g).max(b.b) / 128.;
+        let error = (a.r - b.r)
+            .abs()
+            .max((a.g - b.g).abs())
+            .max((a.b - b.b).abs());
+        assert!(error <= tolerance, "{:?} != {:?}", a, b);
+    }
+
+    let output = radiant(&[
+        "convert",
+        FIXTURE,
+        &path("image.ppm"),
+        "--exposure",
+        "-1",
+        "--curve",
+        "gamma=2.2",
+    ]);
+    assert!(output.status.success());
+    let ppm = fs::read(path("image.ppm")).unwrap();
+    assert!(ppm.starts_with(b"P6\n16 8\n255\n"));
+    assert_eq!(ppm.len(), b"P6\n16 8\n255\n".len() + 16 * 8 * 3);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cli_convert_unsupported_format() {
+    let dir = temp_dir("unsupported");
+    let existing = dir.join("image.png");
+    fs::write(&existing, b"not a radiant image").unwrap();
+
+    // The output format is checked before the output file is touched
+    let output = radiant(&["convert", FIXTURE, existing.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("unsupported output format"));
+    assert_eq!(fs::read(&existing).unwrap(), b"not a radiant image");
+
+    fs::remove_dir_all(dir).unwrap();
+}
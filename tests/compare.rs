@@ -0,0 +1,90 @@
+#![cfg(feature = "std")]
+
+use radiant::compare::{self, Tolerance};
+use radiant::{Image, Rgb};
+
+fn uniform(width: usize, height: usize, v: f32) -> Image {
+    Image {
+        width,
+        height,
+        data: vec![Rgb { r: v, g: v, b: v }; width * height],
+    }
+}
+
+#[test]
+fn compare_identical() {
+    let image = uniform(4, 3, 0.5);
+    let metrics = compare::metrics(&image, &image);
+
+    assert_eq!(metrics.mse, 0.);
+    assert_eq!(metrics.rmse, 0.);
+    assert_eq!(metrics.log_psnr, f32::INFINITY);
+    assert_eq!(metrics.relative_error, 0.);
+    assert_eq!(metrics.flip, 0.);
+
+    let difference = compare::difference(&image, &image);
+    assert_eq!((difference.width, difference.height), (4, 3));
+    assert!(difference.data.iter().all(|&p| p == Rgb::zero()));
+}
+
+#[test]
+fn compare_metrics() {
+    let reference = uniform(2, 1, 1.);
+    let mut image = reference.clone();
+    image.data[0] = Rgb {
+        r: 3.,
+        g: 1.,
+        b: 1.,
+    };
+
+    // One channel out of six is off by 2
+    assert_eq!(compare::mse(&image, &reference), 4. / 6.);
+    assert_eq!(compare::rmse(&image, &reference), (4_f32 / 6.).sqrt());
+    assert!((compare::relative_error(&image, &reference) - 2. / 1.01 / 6.).abs() < 1e-6);
+
+    // log2(1 + 3) - log2(1 + 1) = 1, with a peak of log2(1 + 1) = 1
+    let psnr = 10. * (6_f32).log10();
+    assert!((compare::log_psnr(&image, &reference) - psnr).abs() < 1e-4);
+
+    let errors = compare::flip_errors(&image, &reference);
+    assert!(errors[0] > 0.1 && errors[0] <= 1.);
+    assert_eq!(errors[1], 0.);
+}
+
+#[test]
+fn compare_tolerance() {
+    let reference = uniform(8, 8, 0.5);
+    let slightly_off = uniform(8, 8, 0.501);
+    let far_off = uniform(8, 8, 2.);
+
+    let tolerance = Tolerance {
+        relative_error: Some(0.01),
+        flip: Some(0.05),
+        ..Tolerance::default()
+    };
+    compare::assert_close(&slightly_off, &reference, &tolerance);
+    assert!(!compare::metrics(&far_off, &reference).passes(&tolerance));
+
+    let psnr = Tolerance {
+        log_psnr: Some(40.),
+        ..Tolerance::default()
+    };
+    assert!(compare::metrics(&slightly_off, &reference).passes(&psnr));
+    assert!(!compare::metrics(&far_off, &reference).passes(&psnr));
+}
+
+#[test]
+#[should_panic(expected = "the images differ by more than the tolerance")]
+fn compare_assert_close_fails() {
+    let tolerance = Tolerance {
+        rmse: Some(0.1),
+        ..Tolerance::default()
+    };
+    compare::assert_close(&uniform(1, 1, 1.), &uniform(1, 1, 0.), &tolerance);
+}
+
+#[test]
+#[should_panic(expected = "the images must have the same dimensions")]
+fn compare_mismatched_dimensions() {
+    compare::mse(&uniform(2, 1, 0.), &uniform(1, 2, 0.));
+}
@@ -0,0 +1,140 @@
+use radiant::io::ErrorKind;
+use radiant::{Decoded, Decoder, Rgb};
+use std::time::{Duration, Instant};
+
+fn new_rle_image(width: usize, height: usize) -> Vec<u8> {
+    let mut file = format!(
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        height, width
+    )
+    .into_bytes();
+    for y in 0..height {
+        file.extend_from_slice(&[2, 2, (width >> 8) as u8, width as u8]);
+        for chunk in (0..width).collect::<Vec<_>>().chunks(128) {
+            file.push(chunk.len() as u8);
+            file.extend(chunk.iter().map(|&x| (x * 5 + y) as u8));
+        }
+        for &value in &[y as u8, 200, 131] {
+            let mut left = width;
+            while left > 0 {
+                let count = left.min(127);
+                file.extend_from_slice(&[128 | count as u8, value]);
+                left -= count;
+            }
+        }
+    }
+    file
+}
+
+/// Feed the file in chunks of the given size, collecting every decoded scanline.
+fn decode_in_chunks(file: &[u8], chunk_size: usize) -> (usize, usize, Vec<Rgb>) {
+    let mut decoder = Decoder::new();
+    let mut dimensions = None;
+    let mut data = Vec::new();
+
+    for chunk in file.chunks(chunk_size) {
+        decoder.feed(chunk);
+        loop {
+            match decoder.decode().unwrap() {
+                Decoded::NeedMoreData => break,
+                Decoded::Header { width, height } => dimensions = Some((width, height)),
+                Decoded::Scanline { y, scanline } => {
+                    let (width, _) = dimensions.unwrap();
+                    assert_eq!(data.len(), y * width);
+                    data.extend_from_slice(scanline);
+                }
+                Decoded::Done => {
+                    let (width, height) = dimensions.unwrap();
+                    return (width, height, data);
+                }
+            }
+        }
+    }
+
+    panic!("the decoder never finished");
+}
+
+#[test]
+fn decode_any_chunk_size() {
+    let file = new_rle_image(140, 3);
+    let expected = radiant::load(&file[..]).unwrap();
+
+    for &chunk_size in &[1, 2, 7, 64, 1000, file.len()] {
+        let (width, height, data) = decode_in_chunks(&file, chunk_size);
+        assert_eq!((width, height), (140, 3));
+        assert_eq!(data, expected.data);
+    }
+}
+
+#[test]
+fn decode_byte_by_byte_in_linear_time() {
+    // The fastest of a few runs, to keep the comparison stable
+    let time = |file: &[u8]| {
+        (0..3)
+            .map(|_| {
+                let start = Instant::now();
+                decode_in_chunks(file, 1);
+                start.elapsed()
+            })
+            .min()
+            .unwrap_or(Duration::ZERO)
+    };
+
+    let small = new_rle_image(512, 16);
+    let large = new_rle_image(4096, 16);
+    let (small_time, large_time) = (time(&small), time(&large));
+
+    // The large image is eight times the size, so decoding it should take about eight times as
+    // long, rather than the 64 times of starting over on every byte
+    assert!(
+        large_time < small_time * 24,
+        "{:?} for the large image, {:?} for the small one",
+        large_time,
+        small_time
+    );
+}
+
+#[test]
+fn decode_reports_progress() {
+    let file = b"#?RADIANCE\0\n\n-Y 2 +X 1\n\xff\x00\xff\x80\x00\xff\x00\x80";
+    let mut decoder = Decoder::new();
+
+    decoder.feed(&file[..20]);
+    assert_eq!(decoder.decode().unwrap(), Decoded::NeedMoreData);
+
+    decoder.feed(&file[20..26]);
+    assert_eq!(
+        decoder.decode().unwrap(),
+        Decoded::Header {
+            width: 1,
+            height: 2
+        }
+    );
+    assert_eq!(decoder.decode().unwrap(), Decoded::NeedMoreData);
+
+    decoder.feed(&file[26..]);
+    let scanline = match decoder.decode().unwrap() {
+        Decoded::Scanline { y: 0, scanline } => scanline.to_vec(),
+        other => panic!("unexpected {:?}", other),
+    };
+    assert_eq!(
+        scanline,
+        [Rgb {
+            r: 1.,
+            g: 0.,
+            b: 1.
+        }]
+    );
+    assert!(matches!(
+        decoder.decode().unwrap(),
+        Decoded::Scanline { y: 1, .. }
+    ));
+    assert_eq!(decoder.decode().unwrap(), Decoded::Done);
+}
+
+#[test]
+fn decode_invalid() {
+    let mut decoder = Decoder::new();
+    decoder.feed(b"#?RADIANCE\n\n-Y 1 +Q 1\n");
+    assert_eq!(decoder.decode().unwrap_err().kind(), ErrorKind::InvalidData);
+}
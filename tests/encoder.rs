@@ -0,0 +1,183 @@
+#![cfg(feature = "std")]
+
+use radiant::io::ErrorKind;
+use radiant::{Encoder, Header, Image, Rgb};
+
+fn gradient(width: usize, height: usize) -> Image {
+    let data = (0..width * height)
+        .map(|i| {
+            // Long runs of identical pixels alongside rows of distinct ones
+            let x = i % width;
+            let v = if x < width / 2 { 0.5 } else { x as f32 * 0.37 };
+            Rgb {
+                r: v,
+                g: v * 0.25,
+                b: (i / width) as f32 * 1000.,
+            }
+        })
+        .collect();
+
+    Image {
+        width,
+        height,
+        data,
+    }
+}
+
+fn assert_close(actual: &Image, expected: &Image) {
+    assert_eq!(
+        (actual.width, actual.height),
+        (expected.width, expected.height)
+    );
+    for (a, e) in actual.data.iter().zip(&expected.data) {
+        // Every channel shares the exponent of the brightest one
+        let tolerance = e.r.max(e.g).max(e.b) / 250.;
+        for (a, e) in [(a.r, e.r), (a.g, e.g), (a.b, e.b)] {
+            assert!((a - e).abs() <= tolerance, "{:?} != {:?}", a, e);
+        }
+    }
+}
+
+#[test]
+fn encode_run_length_round_trip() {
+    let image = gradient(300, 3);
+    let mut file = Vec::new();
+    Encoder::new(&mut file).encode(&image).unwrap();
+
+    let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 3 +X 300\n";
+    assert_eq!(&file[..header.len()], header);
+    assert_eq!(&file[header.len()..header.len() + 4], &[2, 2, 1, 44]);
+    // The runs make it smaller than flat pixels would be
+    assert!(file.len() < header.len() + 300 * 3 * 4);
+    assert_close(&radiant::load(&file[..]).unwrap(), &image);
+}
+
+#[test]
+fn encode_flat_round_trip() {
+    let image = gradient(16, 2);
+    let mut file = Vec::new();
+    Encoder::new(&mut file)
+        .run_length_encoding(false)
+        .encode(&image)
+        .unwrap();
+
+    let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 16\n";
+    assert_eq!(&file[..header.len()], header);
+    assert_eq!(file.len(), header.len() + 16 * 2 * 4);
+    assert_close(&radiant::load(&file[..]).unwrap(), &image);
+}
+
+#[test]
+fn encode_special_values() {
+    let image = Image {
+        width: 4,
+        height: 1,
+        data: vec![
+            Rgb::zero(),
+            Rgb {
+                r: -1.,
+                g: f32::NAN,
+                b: 0.,
+            },
+            Rgb {
+                r: 1e-40,
+                g: 0.,
+                b: 0.,
+            },
+            Rgb {
+                r: 255. / 256.,
+                g: 0.,
+                b: 0.,
+            },
+        ],
+    };
+
+    let mut file = Vec::new();
+    Encoder::new(&mut file).encode(&image).unwrap();
+    let loaded = radiant::load(&file[..]).unwrap();
+
+    assert_eq!(&loaded.data[..3], &[Rgb::zero(); 3]);
+    assert!((loaded.data[3].r - 255. / 256.).abs() < 1. / 255.);
+}
+
+#[test]
+fn encode_mismatched_dimensions() {
+    let image = Image {
+        width: 4,
+        height: 4,
+        data: vec![Rgb::zero(); 3],
+    };
+    let error = Encoder::new(Vec::new()).encode(&image).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn encode_pixel_aspect() {
+    let image = gradient(8, 2);
+
+    let mut file = Vec::new();
+    Encoder::new(&mut file)
+        .pixel_aspect(0.75)
+        .encode(&image)
+        .unwrap();
+    let loader = radiant::Loader::new(&file[..]).unwrap();
+    assert_eq!(loader.header.get("PIXASPECT"), Some("0.75"));
+    assert_eq!(loader.header.pixel_aspect(), 0.75);
+
+    // Square pixels are the default, and aren't written
+    let mut file = Vec::new();
+    Encoder::new(&mut file).encode(&image).unwrap();
+    let loader = radiant::Loader::new(&file[..]).unwrap();
+    assert_eq!(loader.header.get("PIXASPECT"), None);
+
+    // Neither are invalid ratios
+    for &invalid in &[f32::NAN, f32::INFINITY, 0., -2.] {
+        let mut file = Vec::new();
+        Encoder::new(&mut file)
+            .pixel_aspect(invalid)
+            .encode(&image)
+            .unwrap();
+        let loader = radiant::Loader::new(&file[..]).unwrap();
+        assert_eq!(loader.header.get("PIXASPECT"), None);
+    }
+}
+
+#[test]
+fn encode_header() {
+    let image = gradient(8, 2);
+    let header = Header {
+        variables: vec![
+            ("FORMAT".into(), "32-bit_rgbe".into()),
+            ("EXPOSURE".into(), "0.5".into()),
+            ("PIXASPECT".into(), "2".into()),
+            ("VIEW".into(), "-vtv -vp 0 0 0".into()),
+        ],
+        other: vec!["# copied".into(), "".into()],
+    };
+
+    let mut file = Vec::new();
+    Encoder::new(&mut file)
+        .pixel_aspect(0.75)
+        .header(header.clone())
+        .encode(&image)
+        .unwrap();
+    let loader = radiant::Loader::new(&file[..]).unwrap();
+    assert_eq!(loader.header.format(), Some("32-bit_rle_rgbe"));
+    assert_eq!(loader.header.pixel_aspect(), 0.75);
+    assert_eq!(loader.header.exposure(), 0.5);
+    assert_eq!(loader.header.get("VIEW"), Some("-vtv -vp 0 0 0"));
+    assert_eq!(loader.header.variables.len(), 4);
+    assert_eq!(loader.header.other, ["# copied"]);
+    assert_close(&loader.load_image().unwrap(), &image);
+
+    // A line break would end the header early
+    let mut header = header;
+    header
+        .variables
+        .push(("SOFTWARE".into(), "one\n\ntwo".into()));
+    let error = Encoder::new(Vec::new())
+        .header(header)
+        .encode(&image)
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,203 @@
+#![cfg(feature = "std")]
+
+use radiant::falsecolor::{FalseColor, Mode, Palette, Scale};
+use radiant::{Image, Rgb, LUMINOUS_EFFICACY};
+
+/// A row of pixels with the given luminances in cd/m².
+fn row(luminances: &[f32]) -> Image {
+    let data = luminances
+        .iter()
+        .map(|&l| {
+            let v = l / LUMINOUS_EFFICACY;
+            Rgb { r: v, g: v, b: v }
+        })
+        .collect();
+
+    Image {
+        width: luminances.len(),
+        height: 1,
+        data,
+    }
+}
+
+fn assert_colour(actual: Rgb, expected: Rgb) {
+    let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+    assert!(
+        close(actual.r, expected.r) && close(actual.g, expected.g) && close(actual.b, expected.b),
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn false_color_linear_and_log() {
+    let grayscale = FalseColor {
+        palette: Palette::Grayscale,
+        max: 100.,
+        ..FalseColor::default()
+    };
+    let image = row(&[0., 25., 100., 400.]);
+    let rendered = grayscale.apply(&image);
+    let levels: Vec<f32> = rendered.data.iter().map(|p| p.g).collect();
+    for (level, expected) in levels.iter().zip(&[0., 0.25, 1., 1.]) {
+        assert!((level - expected).abs() < 1e-4);
+    }
+
+    let log = FalseColor {
+        scale: Scale::Log { decades: 2. },
+        ..grayscale
+    };
+    let rendered = log.apply(&row(&[1., 10., 100., 0.]));
+    let levels: Vec<f32> = rendered.data.iter().map(|p| p.g).collect();
+    for (level, expected) in levels.iter().zip(&[0., 0.5, 1., 0.]) {
+        assert!((level - expected).abs() < 1e-4);
+    }
+    assert_eq!(log.apply_rgb8(&row(&[10.]))[0], [128, 128, 128]);
+}
+
+#[test]
+fn false_color_bands_and_lines() {
+    let bands = FalseColor {
+        palette: Palette::custom(vec![
+            Rgb::zero(),
+            Rgb {
+                r: 1.,
+                g: 0.,
+                b: 0.,
+            },
+        ])
+        .unwrap(),
+        max: 100.,
+        mode: Mode::Bands(2),
+        ..FalseColor::default()
+    };
+    let image = row(&[10., 40., 60., 90.]);
+    let rendered = bands.apply(&image);
+    assert_colour(
+        rendered.data[0],
+        Rgb {
+            r: 0.25,
+            g: 0.,
+            b: 0.,
+        },
+    );
+    assert_colour(
+        rendered.data[1],
+        Rgb {
+            r: 0.25,
+            g: 0.,
+            b: 0.,
+        },
+    );
+    assert_colour(
+        rendered.data[2],
+        Rgb {
+            r: 0.75,
+            g: 0.,
+            b: 0.,
+        },
+    );
+    assert_colour(
+        rendered.data[3],
+        Rgb {
+            r: 0.75,
+            g: 0.,
+            b: 0.,
+        },
+    );
+
+    let lines = FalseColor {
+        mode: Mode::Lines(2),
+        ..bands
+    };
+    let rendered = lines.apply(&image);
+    // Only the pixel before the band changes is on the contour
+    assert_eq!(rendered.data[0], Rgb::zero());
+    assert_colour(
+        rendered.data[1],
+        Rgb {
+            r: 0.25,
+            g: 0.,
+            b: 0.,
+        },
+    );
+    assert_eq!(rendered.data[2], Rgb::zero());
+    assert_eq!(rendered.data[3], Rgb::zero());
+}
+
+#[test]
+fn false_color_legend() {
+    let false_color = FalseColor {
+        scale: Scale::Log { decades: 3. },
+        max: 1000.,
+        mode: Mode::Bands(3),
+        ..FalseColor::default()
+    };
+    let legend = false_color.legend();
+    let ranges: Vec<(f32, f32)> = legend.iter().map(|e| (e.min, e.max)).collect();
+    for ((min, max), (expected_min, expected_max)) in
+        ranges.iter().zip(&[(1., 10.), (10., 100.), (100., 1000.)])
+    {
+        assert!((min - expected_min).abs() < 1e-2 && (max - expected_max).abs() < 1e-1);
+    }
+
+    // The colour bar shows the same colours, brightest at the top
+    let bar = false_color.legend_image(2, 3);
+    assert_eq!((bar.width, bar.height), (2, 3));
+    assert_eq!(*bar.pixel(1, 0), legend[2].colour);
+    assert_eq!(*bar.pixel(0, 2), legend[0].colour);
+
+    assert_eq!(FalseColor::default().legend().len(), 8);
+}
+
+#[test]
+fn false_color_zero_bands() {
+    // Zero bands are treated as one, both in the rendering and in its legend
+    let image = row(&[0., 50., 100., 1000.]);
+    for mode in [Mode::Bands(0), Mode::Lines(0)] {
+        let false_color = FalseColor {
+            max: 100.,
+            mode,
+            ..FalseColor::default()
+        };
+        let legend = false_color.legend();
+        assert_eq!(legend.len(), 1);
+
+        let expected = match mode {
+            Mode::Bands(_) => legend[0].colour,
+            _ => Rgb::zero(),
+        };
+        let rendered = false_color.apply(&image);
+        for &pixel in &rendered.data {
+            assert!(pixel.r.is_finite() && pixel.g.is_finite() && pixel.b.is_finite());
+            assert_eq!(pixel, expected);
+        }
+        assert_eq!(
+            *false_color.legend_image(1, 1).pixel(0, 0),
+            legend[0].colour
+        );
+    }
+}
+
+#[test]
+fn false_color_custom_palette() {
+    assert_eq!(Palette::custom(Vec::new()), None);
+    assert_eq!(Palette::custom(vec![Rgb::zero()]), None);
+
+    let white = Rgb {
+        r: 1.,
+        g: 1.,
+        b: 1.,
+    };
+    let palette = Palette::custom(vec![white, Rgb::zero(), white]).unwrap();
+    match &palette {
+        Palette::Custom(custom) => assert_eq!(custom.stops().len(), 3),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_colour(palette.colour(-1.), white);
+    assert_colour(palette.colour(0.5), Rgb::zero());
+    assert_colour(palette.colour(0.75), white * 0.5);
+    assert_colour(palette.colour(f32::NAN), white);
+    assert_colour(palette.colour(2.), white);
+}
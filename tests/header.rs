@@ -0,0 +1,98 @@
+use radiant::io::ErrorKind;
+use radiant::Loader;
+
+#[test]
+fn header_variables() {
+    let file = b"#?RADIANCE\n\
+        # made by hand\n\
+        FORMAT=32-bit_rle_rgbe\n\
+        EXPOSURE= 2.5\n\
+        pfilt -x /2 -y /2\n\
+        EXPOSURE=0.5 \n\
+        \n-Y 1 +X 1\n\x80\x80\x80\x80";
+    let loader = Loader::new(&file[..]).unwrap();
+    let header = &loader.header;
+
+    assert_eq!(header.format(), Some("32-bit_rle_rgbe"));
+    assert_eq!(header.get("EXPOSURE"), Some("0.5"));
+    assert_eq!(header.get("VIEW"), None);
+    assert_eq!(header.variables.len(), 3);
+    assert_eq!(header.other, ["# made by hand", "pfilt -x /2 -y /2"]);
+
+    assert_eq!(loader.load_image().unwrap().data.len(), 1);
+}
+
+#[test]
+fn header_empty() {
+    let file = b"#?RADIANCE\n\n-Y 1 +X 1\n\x80\x80\x80\x80";
+    let loader = Loader::new(&file[..]).unwrap();
+    assert_eq!(loader.header, Default::default());
+}
+
+#[test]
+fn header_pixel_aspect() {
+    let file =
+        b"#?RADIANCE\nPIXASPECT=0.5\nPIXASPECT= 3\nPIXASPECT=bogus\n\n-Y 1 +X 1\n\x80\x80\x80\x80";
+    let loader = Loader::new(&file[..]).unwrap();
+    assert_eq!(loader.header.pixel_aspect(), 1.5);
+
+    let file = b"#?RADIANCE\n\n-Y 1 +X 1\n\x80\x80\x80\x80";
+    assert_eq!(Loader::new(&file[..]).unwrap().header.pixel_aspect(), 1.);
+}
+
+#[test]
+fn header_exposure() {
+    let file =
+        b"#?RADIANCE\nEXPOSURE=2\nEXPOSURE= 0.25\nEXPOSURE=-1\n\n-Y 1 +X 1\n\x80\x80\x80\x80";
+    let loader = Loader::new(&file[..]).unwrap();
+    assert_eq!(loader.header.exposure(), 0.5);
+
+    let file = b"#?RADIANCE\n\n-Y 1 +X 1\n\x80\x80\x80\x80";
+    assert_eq!(Loader::new(&file[..]).unwrap().header.exposure(), 1.);
+}
+
+#[test]
+fn header_crlf() {
+    let file = b"#?RADIANCE\r\nFORMAT=32-bit_rle_rgbe\r\n# comment\r\n\r\n-Y 1 +X 2\r\n\
+        \x80\x80\x80\x80\x40\x40\x40\x80";
+    let loader = Loader::new(&file[..]).unwrap();
+    assert_eq!((loader.width, loader.height), (2, 1));
+    assert_eq!(loader.header.format(), Some("32-bit_rle_rgbe"));
+    assert_eq!(loader.header.other, ["# comment"]);
+    assert_eq!(loader.load_image().unwrap().data[1].r, 64. / 255.);
+}
+
+#[test]
+fn header_without_blank_line() {
+    // The dimensions are read as another header line, and the pixels after them as well
+    let file = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n-Y 1 +X 1\n\x80\x80\x80\x80";
+    let error = Loader::new(&file[..]).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+    let file = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n-Y 1 +X 1\n\n\x80\x80\x80\x80\n";
+    let error = Loader::new(&file[..]).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn header_lines_without_equals() {
+    let file = b"#?RADIANCE\n\
+        SOFTWARE\n\
+        =32-bit_rle_rgbe\n\
+        VIEW -vtv -vp 0 0 0\n\
+        NAME WITH SPACES=1\n\
+        # EXPOSURE=2\n\
+        \n-Y 1 +X 1\n\x80\x80\x80\x80";
+    let loader = Loader::new(&file[..]).unwrap();
+    assert!(loader.header.variables.is_empty());
+    assert_eq!(
+        loader.header.other,
+        [
+            "SOFTWARE",
+            "=32-bit_rle_rgbe",
+            "VIEW -vtv -vp 0 0 0",
+            "NAME WITH SPACES=1",
+            "# EXPOSURE=2"
+        ]
+    );
+}
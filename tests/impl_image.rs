@@ -0,0 +1,98 @@
+#![cfg(feature = "impl-image")]
+
+use image::{DynamicImage, ImageDecoder, ImageEncoder, Rgb32FImage};
+use radiant::{Encoder, Image, Loader, Rgb};
+use std::convert::TryFrom;
+
+fn sample_file() -> Vec<u8> {
+    let image = Image {
+        width: 12,
+        height: 5,
+        data: (0..60)
+            .map(|i| Rgb {
+                r: i as f32,
+                g: 0.5,
+                b: 1. / (i + 1) as f32,
+            })
+            .collect(),
+    };
+
+    let mut file = Vec::new();
+    Encoder::new(&mut file).encode(&image).unwrap();
+    file
+}
+
+#[test]
+fn image_decoder() {
+    let file = sample_file();
+    let expected = radiant::load(&file[..]).unwrap();
+
+    let decoder = Loader::new(&file[..]).unwrap();
+    assert_eq!(
+        decoder.dimensions(),
+        (expected.width as u32, expected.height as u32)
+    );
+
+    let decoded = DynamicImage::from_decoder(decoder).unwrap().into_rgb32f();
+    assert_eq!(Image::from(decoded).data, expected.data);
+}
+
+#[test]
+fn image_encoder() {
+    let image = Rgb32FImage::from_fn(16, 4, |x, y| image::Rgb([x as f32, y as f32, 0.5]));
+
+    let mut file = Vec::new();
+    Encoder::new(&mut file)
+        .write_image(
+            as_bytes(&image).as_slice(),
+            16,
+            4,
+            image::ExtendedColorType::Rgb32F,
+        )
+        .unwrap();
+
+    let loaded = radiant::load(&file[..]).unwrap();
+    assert_eq!((loaded.width, loaded.height), (16, 4));
+    assert!((loaded.pixel(15, 3).r - 15.).abs() < 0.1);
+    assert!((loaded.pixel(15, 3).g - 3.).abs() < 0.1);
+
+    let error = Encoder::new(Vec::new())
+        .write_image(&[0; 16 * 4 * 3], 16, 4, image::ExtendedColorType::Rgb8)
+        .unwrap_err();
+    assert!(matches!(error, image::ImageError::Unsupported(_)));
+}
+
+#[test]
+fn image_conversions() {
+    let image = Image {
+        width: 2,
+        height: 1,
+        data: vec![
+            Rgb {
+                r: 1.,
+                g: 2.,
+                b: 3.,
+            },
+            Rgb {
+                r: 4.,
+                g: 5.,
+                b: 6.,
+            },
+        ],
+    };
+
+    let converted = Rgb32FImage::try_from(image.clone()).unwrap();
+    assert_eq!(converted.get_pixel(1, 0), &image::Rgb([4., 5., 6.]));
+    assert_eq!(Image::from(converted).data, image.data);
+
+    let mismatched = Image { width: 3, ..image };
+    assert!(Rgb32FImage::try_from(mismatched).is_err());
+}
+
+fn as_bytes(image: &Rgb32FImage) -> Vec<u8> {
+    image
+        .as_raw()
+        .iter()
+        .flat_map(|c| c.to_ne_bytes())
+        .collect()
+}
@@ -0,0 +1,108 @@
+use radiant::{Fill, Loader, Rgb, ScanlineProblem, ScanlineWarning};
+
+const WIDTH: usize = 8;
+const HEIGHT: usize = 4;
+
+/// A file using the new run-length encoding, where every pixel of row `y` has a red value of
+/// `y + 1`.
+fn rows_file() -> Vec<u8> {
+    let mut file = format!("#?RADIANCE\n\n-Y {} +X {}\n", HEIGHT, WIDTH).into_bytes();
+    for y in 0..HEIGHT {
+        file.extend_from_slice(&[2, 2, 0, WIDTH as u8]);
+        for value in [y as u8 + 1, 0, 0, 128] {
+            file.extend_from_slice(&[0x80 | WIDTH as u8, value]);
+        }
+    }
+    file
+}
+
+fn row(image: &radiant::Image, y: usize) -> &[Rgb] {
+    &image.data[y * image.width..(y + 1) * image.width]
+}
+
+#[test]
+fn lenient_clean_file() {
+    let file = rows_file();
+    let recovered = Loader::new(&file[..])
+        .unwrap()
+        .load_image_lenient(Fill::Black)
+        .unwrap();
+    assert!(recovered.warnings.is_empty());
+    assert_eq!(recovered.image.data, radiant::load(&file[..]).unwrap().data);
+}
+
+#[test]
+fn lenient_truncated() {
+    let file = rows_file();
+    let expected = radiant::load(&file[..]).unwrap();
+
+    // Cut the file off halfway through the third scanline
+    let file = &file[..file.len() - 12 - 6];
+    assert!(radiant::load(file).is_err());
+
+    let recovered = Loader::new(file)
+        .unwrap()
+        .load_image_lenient(Fill::Black)
+        .unwrap();
+    assert_eq!(
+        recovered.warnings,
+        [ScanlineWarning {
+            rows: 2..4,
+            problem: ScanlineProblem::Missing,
+        }]
+    );
+    assert_eq!(row(&recovered.image, 1), row(&expected, 1));
+    assert!(recovered.image.data[2 * WIDTH..]
+        .iter()
+        .all(|p| *p == Rgb::zero()));
+}
+
+#[test]
+fn lenient_corrupt_run() {
+    let mut file = rows_file();
+    let expected = radiant::load(&file[..]).unwrap();
+
+    // Make the first run of the second scanline longer than the scanline itself
+    let second = file.len() - 3 * 12;
+    file[second + 4] = 0xff;
+    assert!(radiant::load(&file[..]).is_err());
+
+    let recovered = Loader::new(&file[..])
+        .unwrap()
+        .load_image_lenient(Fill::PreviousScanline)
+        .unwrap();
+    assert_eq!(
+        recovered.warnings,
+        [ScanlineWarning {
+            rows: 1..2,
+            problem: ScanlineProblem::Corrupt,
+        }]
+    );
+    assert_eq!(row(&recovered.image, 1), row(&expected, 0));
+    assert_eq!(row(&recovered.image, 2), row(&expected, 2));
+    assert_eq!(row(&recovered.image, 3), row(&expected, 3));
+}
+
+#[test]
+fn lenient_corrupt_old_rle() {
+    // The run in the first scanline is longer than the rest of it, and old run-length encoding
+    // can't be resynchronised, so the remaining scanlines are lost.
+    let file = b"#?RADIANCE\n\n-Y 3 +X 2\n\
+        \x80\x80\x80\x80\x01\x01\x01\x05\
+        \x80\x80\x80\x80\x80\x80\x80\x80\
+        \x80\x80\x80\x80\x80\x80\x80\x80";
+    let recovered = Loader::new(&file[..])
+        .unwrap()
+        .load_image_lenient(Fill::Black)
+        .unwrap();
+
+    let warnings: Vec<String> = recovered.warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(
+        warnings,
+        [
+            "row 0 contained invalid run-length encoding",
+            "rows 1 to 2 are missing",
+        ]
+    );
+    assert!(recovered.image.data.iter().all(|p| *p == Rgb::zero()));
+}
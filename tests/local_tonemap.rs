@@ -0,0 +1,115 @@
+#![cfg(feature = "std")]
+
+use radiant::local_tonemap::{BilateralToneMap, PhotographicToneMap};
+use radiant::tonemap::{Curve, ToneMap};
+use radiant::{Image, Rgb};
+
+const LINEAR: ToneMap = ToneMap {
+    exposure: 0.,
+    curve: Curve::Linear,
+};
+
+fn gray(v: f32) -> Rgb {
+    Rgb { r: v, g: v, b: v }
+}
+
+/// A dim room on the left and a bright window on the right, each with a faint texture, spanning
+/// five orders of magnitude.
+fn interior() -> Image {
+    let (width, height) = (64, 32);
+    let data = (0..width * height)
+        .map(|i| {
+            let x = i % width;
+            let texture = if (x + i / width) % 2 == 0 { 1.2 } else { 1. };
+            gray(if x < width / 2 { 0.01 } else { 1000. } * texture)
+        })
+        .collect();
+
+    Image {
+        width,
+        height,
+        data,
+    }
+}
+
+fn assert_display_range(image: &Image) {
+    assert!(image
+        .data
+        .iter()
+        .all(|rgb| [rgb.r, rgb.g, rgb.b].iter().all(|c| (0. ..=1.).contains(c))));
+}
+
+#[test]
+fn bilateral_compresses_base_and_keeps_detail() {
+    let tone_map = BilateralToneMap {
+        tone_map: LINEAR,
+        ..BilateralToneMap::default()
+    };
+    let display = tone_map.apply(&interior());
+    assert_display_range(&display);
+
+    // Both the room and the window are visible, and the room keeps its texture
+    let room = (display.pixel(8, 8).g, display.pixel(9, 8).g);
+    assert!(room.0 > 0.05 && room.1 > 0.05, "{:?}", room);
+    assert!((room.0 / room.1 - 1.2).abs() < 0.05, "{:?}", room);
+    assert!(display.pixel(48, 8).g > 0.5);
+
+    // A uniform image maps its base to 1
+    let uniform = Image {
+        width: 4,
+        height: 4,
+        data: vec![gray(3.); 16],
+    };
+    let display = tone_map.apply(&uniform);
+    assert!(display.data.iter().all(|rgb| (rgb.g - 1.).abs() < 1e-3));
+}
+
+#[test]
+fn photographic_dodges_and_burns() {
+    let tone_map = PhotographicToneMap {
+        tone_map: ToneMap {
+            exposure: -1.,
+            ..LINEAR
+        },
+        ..PhotographicToneMap::default()
+    };
+    let display = tone_map.apply(&interior());
+    assert_display_range(&display);
+
+    // Unlike a global curve, the texture survives in the highlights as well as the shadows
+    let window = (display.pixel(48, 8).g, display.pixel(49, 8).g);
+    assert!((window.0 / window.1 - 1.2).abs() < 0.05, "{:?}", window);
+    let room = (display.pixel(8, 8).g, display.pixel(9, 8).g);
+    assert!((room.0 / room.1 - 1.2).abs() < 0.05, "{:?}", room);
+
+    // A uniform image maps to the key, compressed by its surround
+    let uniform = Image {
+        width: 4,
+        height: 4,
+        data: vec![gray(3.); 16],
+    };
+    let display = tone_map.apply(&uniform);
+    let expected = 0.18 / 1.18 / 2.;
+    assert!(display
+        .data
+        .iter()
+        .all(|rgb| (rgb.g - expected).abs() < 1e-3));
+}
+
+#[test]
+fn local_tone_map_rgb8() {
+    let image = interior();
+
+    let bilateral = BilateralToneMap::default();
+    let expected: Vec<[u8; 3]> = bilateral
+        .apply(&image)
+        .data
+        .iter()
+        .map(|rgb| [rgb.r, rgb.g, rgb.b].map(|c| (c * 255. + 0.5) as u8))
+        .collect();
+    assert_eq!(bilateral.apply_rgb8(&image), expected);
+
+    let photographic = PhotographicToneMap::default().apply_rgb8(&image);
+    assert_eq!(photographic.len(), image.data.len());
+    assert!(photographic[8][1] > 0);
+}
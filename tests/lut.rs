@@ -0,0 +1,104 @@
+#![cfg(feature = "std")]
+
+use radiant::io::ErrorKind;
+use radiant::lut::{self, Interpolation, Log2Shaper, Lut};
+use radiant::Rgb;
+
+/// A 2 by 2 by 2 LUT with the given output for every corner, in `.cube` order.
+fn cube(output: impl Fn(f32, f32, f32) -> [f32; 3]) -> String {
+    let mut file = String::from("# made by hand\nTITLE \"test\"\nLUT_3D_SIZE 2\n\n");
+    for b in 0..2 {
+        for g in 0..2 {
+            for r in 0..2 {
+                let [r, g, b] = output(r as f32, g as f32, b as f32);
+                file += &format!("{} {} {}\n", r, g, b);
+            }
+        }
+    }
+    file
+}
+
+fn load(file: &str) -> Lut {
+    lut::load(file.as_bytes()).unwrap()
+}
+
+fn rgb(r: f32, g: f32, b: f32) -> Rgb {
+    Rgb { r, g, b }
+}
+
+#[test]
+fn lut_parse() {
+    let lut = load(&cube(|r, g, b| [b, g, r]));
+    assert_eq!(lut.title.as_deref(), Some("test"));
+    assert_eq!(lut.size, 2);
+    assert_eq!(lut.data[1], rgb(0., 0., 1.));
+    assert_eq!(lut.domain_max, rgb(1., 1., 1.));
+
+    // Domains, and a 1D table that comes before the 3D one
+    let file = format!(
+        "LUT_1D_SIZE 2\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2\n0 0 0\n1 1 1\n{}",
+        cube(|r, g, b| [r, g, b])
+    );
+    let lut = load(&file);
+    assert_eq!(lut.data.len(), 8);
+    assert_eq!(lut.data[7], rgb(1., 1., 1.));
+    assert_eq!(lut.lookup(rgb(1., 2., 0.)), rgb(0.5, 1., 0.));
+}
+
+#[test]
+fn lut_interpolation() {
+    // Linear grades are reproduced exactly by both interpolations
+    let mut lut = load(&cube(|r, g, b| [b, g, r]));
+    let input = rgb(0.2, 0.7, 0.4);
+    for interpolation in [Interpolation::Trilinear, Interpolation::Tetrahedral] {
+        lut.interpolation = interpolation;
+        let output = lut.lookup(input);
+        assert!((output.r - 0.4).abs() < 1e-6 && (output.b - 0.2).abs() < 1e-6);
+    }
+
+    // Trilinear interpolation reproduces products of the channels, tetrahedral does not
+    let mut lut = load(&cube(|r, g, b| [r * g * b; 3]));
+    assert_eq!(lut.lookup(rgb(0.5, 0.5, 0.5)).r, 0.125);
+    lut.interpolation = Interpolation::Tetrahedral;
+    assert_eq!(lut.lookup(rgb(0.5, 0.5, 0.5)).r, 0.5);
+    assert_eq!(lut.lookup(rgb(0.5, 0.25, 0.)).r, 0.);
+
+    // Inputs outside of the domain are clamped
+    assert_eq!(lut.lookup(rgb(2., 2., -1.)).r, 0.);
+}
+
+#[test]
+fn lut_shaper() {
+    let mut lut = load(&cube(|r, g, b| [r, g, b]));
+    lut.shaper = Some(Log2Shaper { min: -4., max: 4. });
+
+    let output = lut.lookup(rgb(1., 16., 1. / 256.));
+    assert_eq!(output, rgb(0.5, 1., 0.));
+
+    let image = radiant::Image {
+        width: 1,
+        height: 1,
+        data: vec![rgb(0.25, 4., 0.)],
+    };
+    assert_eq!(lut.apply(&image).data, [rgb(0.25, 0.75, 0.)]);
+}
+
+#[test]
+fn lut_errors() {
+    let error = |file: &str| lut::load(file.as_bytes()).unwrap_err();
+
+    let truncated = cube(|r, g, b| [r, g, b]).replace("1 1 1\n", "");
+    assert_eq!(error(&truncated).kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        error("LUT_1D_SIZE 2\n0 0 0\n1 1 1\n").to_string(),
+        "the file does not contain a 3D LUT"
+    );
+    assert_eq!(
+        error("LUT_3D_SIZE 2\n0 0 zero\n").to_string(),
+        "the LUT contains an invalid colour"
+    );
+    assert_eq!(
+        error("LUT_3D_SIZE 1\n").to_string(),
+        "the LUT size is invalid"
+    );
+}
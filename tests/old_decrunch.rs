@@ -89,3 +89,17 @@ fn old_decrunch_zero_length_run() {
         },]
     );
 }
+
+#[test]
+fn old_decrunch_every_exponent() {
+    let mut reader = b"#?RADIANCE\0\n\n-Y 1 +X 256\n".to_vec();
+    for e in 0..=255 {
+        reader.extend_from_slice(&[255, 0, 0, e]);
+    }
+    let image = radiant::load(&reader[..]).unwrap();
+    assert_eq!(image.data[0].r, 0.0);
+    for e in 1..=255 {
+        let expected = 255. * (2_f32.powi(e - 128) / 255.);
+        assert_eq!(image.data[e as usize].r, expected);
+    }
+}
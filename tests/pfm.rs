@@ -0,0 +1,94 @@
+#![cfg(feature = "std")]
+
+use radiant::pfm::{self, Channels, Endianness};
+use radiant::{Image, Rgb};
+
+fn sample() -> Image {
+    Image {
+        width: 3,
+        height: 2,
+        data: (0..6)
+            .map(|i| Rgb {
+                r: i as f32 + 0.1,
+                g: -(i as f32),
+                b: 1e-20 * i as f32,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn pfm_round_trip() {
+    let image = sample();
+    for &endianness in &[Endianness::Little, Endianness::Big] {
+        let mut file = Vec::new();
+        pfm::Encoder::new(&mut file)
+            .endianness(endianness)
+            .encode(&image)
+            .unwrap();
+        assert_eq!(pfm::load(&file[..]).unwrap().data, image.data);
+    }
+}
+
+#[test]
+fn pfm_bottom_to_top() {
+    // A big-endian 1x2 grayscale image, with the bottom row first
+    let mut file = b"Pf\n1 2\n1.0\n".to_vec();
+    file.extend_from_slice(&2_f32.to_be_bytes());
+    file.extend_from_slice(&7_f32.to_be_bytes());
+
+    let image = pfm::load(&file[..]).unwrap();
+    assert_eq!(
+        image.data,
+        [
+            Rgb {
+                r: 7.,
+                g: 7.,
+                b: 7.
+            },
+            Rgb {
+                r: 2.,
+                g: 2.,
+                b: 2.
+            }
+        ]
+    );
+
+    let mut written = Vec::new();
+    pfm::Encoder::new(&mut written)
+        .channels(Channels::Grayscale)
+        .endianness(Endianness::Big)
+        .encode(&image)
+        .unwrap();
+    assert_eq!(written, file);
+}
+
+#[test]
+fn pfm_little_endian_header() {
+    // Any whitespace may separate the header fields, and the scale's magnitude is ignored
+    let mut file = b"PF 1\t1\n-4.5\n".to_vec();
+    for v in [1_f32, 2., 3.] {
+        file.extend_from_slice(&v.to_le_bytes());
+    }
+    let image = pfm::load(&file[..]).unwrap();
+    assert_eq!(
+        image.data,
+        [Rgb {
+            r: 1.,
+            g: 2.,
+            b: 3.
+        }]
+    );
+}
+
+#[test]
+fn pfm_errors() {
+    let error = pfm::load(&b"P6\n1 1\n255\n"[..]).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    let error = pfm::load(&b"PF\n1 x\n-1\n"[..]).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    let error = pfm::load(&b"PF\n2 2\n-1\n\0\0\0\0"[..]).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "std")]
+
+use radiant::{Image, Rgb, LUMINOUS_EFFICACY};
+use std::f32::consts::PI;
+
+fn uniform(width: usize, height: usize, v: f32) -> Image {
+    Image {
+        width,
+        height,
+        data: vec![Rgb { r: v, g: v, b: v }; width * height],
+    }
+}
+
+#[test]
+fn luminance_cd_per_m2() {
+    let rgb = Rgb {
+        r: 1.,
+        g: 2.,
+        b: 4.,
+    };
+    let expected = 179. * (0.265 + 2. * 0.670 + 4. * 0.065);
+    assert!((rgb.luminance() - expected).abs() < 1e-3);
+
+    let image = uniform(2, 1, 1.);
+    assert_eq!(image.luminance(), [LUMINOUS_EFFICACY; 2]);
+}
+
+#[test]
+fn illuminance_uniform_sphere() {
+    // Any plane sees a hemisphere of uniform luminance L, which gives an illuminance of pi * L
+    let image = uniform(128, 64, 1.);
+    let expected = PI * LUMINOUS_EFFICACY;
+    for normal in [[0., 1., 0.], [1., 0., 0.], [0., 0., -3.], [1., 1., 1.]] {
+        let lux = image.illuminance(normal);
+        assert!(
+            (lux - expected).abs() / expected < 1e-3,
+            "{:?}: {}",
+            normal,
+            lux
+        );
+    }
+}
+
+#[test]
+fn illuminance_sky_on_vertical_plane() {
+    // Only the upper half of the image is lit, so a vertical plane sees half of it
+    let mut image = uniform(128, 64, 1.);
+    image.data[128 * 32..].fill(Rgb::zero());
+
+    let expected = PI * LUMINOUS_EFFICACY / 2.;
+    let lux = image.illuminance([0., 0., 1.]);
+    assert!((lux - expected).abs() / expected < 1e-2, "{}", lux);
+    assert!(image.illuminance([0., -1., 0.]) < 1e-3 * expected);
+}
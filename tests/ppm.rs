@@ -0,0 +1,105 @@
+#![cfg(feature = "std")]
+
+use radiant::tonemap::{Curve, ToneMap};
+use radiant::{Image, Rgb};
+
+const LINEAR: ToneMap = ToneMap {
+    exposure: 0.,
+    curve: Curve::Linear,
+};
+
+fn sample() -> Image {
+    Image {
+        width: 2,
+        height: 1,
+        data: vec![
+            Rgb {
+                r: 0.5,
+                g: 2.,
+                b: -1.,
+            },
+            Rgb {
+                r: 0.25,
+                g: 0.,
+                b: f32::NAN,
+            },
+        ],
+    }
+}
+
+#[test]
+fn ppm_8_bit() {
+    let mut file = Vec::new();
+    sample().write_ppm(&mut file, LINEAR).unwrap();
+    assert_eq!(file, b"P6\n2 1\n255\n\x80\xff\x00\x40\x00\x00");
+
+    // One stop brighter doubles the linear values
+    let brighter = ToneMap {
+        exposure: 1.,
+        ..LINEAR
+    };
+    assert_eq!(sample().to_rgb8(brighter), [[255, 255, 0], [128, 0, 0]]);
+}
+
+#[test]
+fn ppm_16_bit() {
+    let mut file = Vec::new();
+    sample().write_ppm16(&mut file, LINEAR).unwrap();
+
+    let header = b"P6\n2 1\n65535\n";
+    assert_eq!(&file[..header.len()], header);
+    assert_eq!(
+        &file[header.len()..],
+        &[0x80, 0x00, 0xff, 0xff, 0, 0, 0x40, 0x00, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn pgm_16_bit_luminance() {
+    let image = Image {
+        width: 1,
+        height: 2,
+        data: vec![
+            Rgb {
+                r: 1.,
+                g: 1.,
+                b: 1.,
+            },
+            Rgb {
+                r: 0.,
+                g: 1.,
+                b: 0.,
+            },
+        ],
+    };
+
+    let mut file = Vec::new();
+    image.write_pgm16(&mut file, LINEAR).unwrap();
+
+    let header = b"P5\n1 2\n65535\n";
+    assert_eq!(&file[..header.len()], header);
+    let samples: Vec<u16> = file[header.len()..]
+        .chunks(2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .collect();
+    assert_eq!(samples, [65535, (0.670 * 65535_f32).round() as u16]);
+}
+
+#[test]
+fn tone_curves() {
+    let apply = |curve, v| {
+        ToneMap {
+            exposure: 0.,
+            curve,
+        }
+        .apply(Rgb { r: v, g: v, b: v })
+        .r
+    };
+
+    assert!((apply(Curve::Gamma(2.), 0.25) - 0.5).abs() < 1e-6);
+    assert!((apply(Curve::Srgb, 0.5) - 0.735_357).abs() < 1e-4);
+    assert!((apply(Curve::Reinhard, 1.) - apply(Curve::Srgb, 0.5)).abs() < 1e-6);
+    assert!((apply(Curve::Reinhard, f32::INFINITY) - 1.).abs() < 1e-6);
+    assert_eq!(apply(Curve::Aces, 0.), 0.);
+    assert!(apply(Curve::Aces, 1e6) <= 1.);
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "std")]
+
+use radiant::projection::Projection;
+use radiant::{Image, Rgb};
+
+const PROJECTIONS: [Projection; 4] = [
+    Projection::Equirectangular,
+    Projection::MirrorBall,
+    Projection::AngularMap,
+    Projection::Cubemap,
+];
+
+fn assert_close(a: [f32; 3], b: [f32; 3]) {
+    let distance = a
+        .iter()
+        .zip(&b)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0., f32::max);
+    assert!(distance < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn direction_convention() {
+    let forward = [0., 0., -1.];
+    let backward = [0., 0., 1.];
+    let up = [0., 1., 0.];
+    let right = [1., 0., 0.];
+
+    let equirect = Projection::Equirectangular;
+    assert_close(equirect.direction(0.5, 0.5).unwrap(), forward);
+    assert_close(equirect.direction(0.75, 0.5).unwrap(), right);
+    assert_close(equirect.direction(0.5, 0.).unwrap(), up);
+
+    assert_close(
+        Projection::MirrorBall.direction(0.5, 0.5).unwrap(),
+        backward,
+    );
+    assert_close(
+        Projection::AngularMap.direction(0.5, 0.5).unwrap(),
+        backward,
+    );
+    assert_close(Projection::AngularMap.direction(0.75, 0.5).unwrap(), right);
+    assert_eq!(Projection::MirrorBall.direction(0., 0.), None);
+
+    assert_close(Projection::Cubemap.direction(0.5 / 6., 0.5).unwrap(), right);
+    assert_close(Projection::Cubemap.direction(2.5 / 6., 0.5).unwrap(), up);
+    assert_close(
+        Projection::Cubemap.direction(5.5 / 6., 0.5).unwrap(),
+        forward,
+    );
+}
+
+#[test]
+fn coordinates_round_trip() {
+    for &projection in &PROJECTIONS {
+        for &direction in &[
+            [0.3, 0.5, -0.7],
+            [-0.8, -0.1, 0.2],
+            [0.1, 0.9, 0.4],
+            [0.5, -0.6, -0.1],
+        ] {
+            let (u, v) = projection.coordinates(direction);
+            let length = direction.iter().map(|c| c * c).sum::<f32>().sqrt();
+            let unit = [
+                direction[0] / length,
+                direction[1] / length,
+                direction[2] / length,
+            ];
+            assert_close(projection.direction(u, v).unwrap(), unit);
+        }
+    }
+}
+
+#[test]
+fn reproject_constant() {
+    let gray = Rgb {
+        r: 0.5,
+        g: 0.5,
+        b: 0.5,
+    };
+    let image = Image {
+        width: 16,
+        height: 8,
+        data: vec![gray; 16 * 8],
+    };
+
+    let cubemap = image.reproject(Projection::Equirectangular, Projection::Cubemap, 24, 4);
+    assert!(cubemap.data.iter().all(|&px| px == gray));
+
+    let probe = cubemap.reproject(Projection::Cubemap, Projection::AngularMap, 8, 8);
+    assert_eq!(probe.pixel(0, 0), &Rgb::zero());
+    assert_eq!(probe.pixel(4, 4), &gray);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+use radiant::{Loader, Rgb, ScanlineIndex};
+use std::io::{Cursor, ErrorKind};
+
+/// Build an image using the new run-length encoding, where each row has a distinct color.
+fn new_rle_image(width: usize, height: usize) -> Vec<u8> {
+    let mut file = format!("#?RADIANCE\n\n-Y {} +X {}\n", height, width).into_bytes();
+    for y in 0..height {
+        file.extend_from_slice(&[2, 2, (width >> 8) as u8, width as u8]);
+        for &value in &[y as u8, 255 - y as u8, 7, 128] {
+            let mut left = width;
+            while left > 0 {
+                let count = left.min(127);
+                file.extend_from_slice(&[128 | count as u8, value]);
+                left -= count;
+            }
+        }
+    }
+    file
+}
+
+#[test]
+fn random_order() {
+    let file = new_rle_image(200, 6);
+    let full = radiant::load(&file[..]).unwrap();
+
+    let mut loader = Loader::new(Cursor::new(&file))
+        .unwrap()
+        .index_scanlines()
+        .unwrap();
+    assert_eq!(loader.index().height(), 6);
+
+    let mut buffer = vec![Rgb::zero(); 200];
+    for &y in &[4, 0, 5, 1, 2, 3, 3] {
+        loader.read_scanline(y, &mut buffer).unwrap();
+        assert_eq!(&buffer[..], &full.data[y * 200..(y + 1) * 200]);
+    }
+
+    let error = loader.read_scanline(6, &mut buffer).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn save_and_reload_index() {
+    let file = new_rle_image(32, 4);
+    let index = Loader::new(Cursor::new(&file))
+        .unwrap()
+        .index_scanlines()
+        .unwrap()
+        .index()
+        .clone();
+
+    let mut saved = Vec::new();
+    index.write_to(&mut saved).unwrap();
+    let reloaded = ScanlineIndex::read_from(&saved[..]).unwrap();
+    assert_eq!(reloaded, index);
+
+    let mut loader = Loader::new(Cursor::new(&file))
+        .unwrap()
+        .with_index(reloaded)
+        .unwrap();
+    let mut buffer = vec![Rgb::zero(); 32];
+    loader.read_scanline(2, &mut buffer).unwrap();
+    assert_eq!(buffer[0].r, 2. / 255.);
+}
+
+#[test]
+fn mismatched_index() {
+    let index = Loader::new(Cursor::new(new_rle_image(32, 4)))
+        .unwrap()
+        .index_scanlines()
+        .unwrap()
+        .index()
+        .clone();
+
+    let error = Loader::new(Cursor::new(new_rle_image(32, 5)))
+        .unwrap()
+        .with_index(index)
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,102 @@
+use radiant::io::ErrorKind;
+use radiant::Loader;
+
+/// Build an image where every pixel has a distinct value, using the new run-length encoding with
+/// a mix of runs and literal spans.
+fn new_rle_image(width: usize, height: usize) -> Vec<u8> {
+    let mut file = format!("#?RADIANCE\n\n-Y {} +X {}\n", height, width).into_bytes();
+    for y in 0..height {
+        file.extend_from_slice(&[2, 2, (width >> 8) as u8, width as u8]);
+        // Red is a gradient, written as literals
+        for chunk in (0..width).collect::<Vec<_>>().chunks(128) {
+            file.push(chunk.len() as u8);
+            file.extend(chunk.iter().map(|&x| x as u8));
+        }
+        // Green, blue and exponent are constant per row, written as runs
+        for value in &[y as u8, 255, 136] {
+            let mut left = width;
+            while left > 0 {
+                let count = left.min(127);
+                file.extend_from_slice(&[128 | count as u8, *value]);
+                left -= count;
+            }
+        }
+    }
+    file
+}
+
+/// Build an image using the old run-length encoding, with runs of repeated pixels.
+fn old_rle_image() -> Vec<u8> {
+    let mut file = b"#?RADIANCE\n\n-Y 3 +X 4\n".to_vec();
+    for y in 0..3 {
+        file.extend_from_slice(&[10 * y, 0, 0, 128]);
+        file.extend_from_slice(&[1, 1, 1, 2]);
+        file.extend_from_slice(&[10 * y + 1, 0, 0, 128]);
+    }
+    file
+}
+
+#[test]
+fn region_matches_full_load() {
+    let file = new_rle_image(300, 5);
+    let full = radiant::load(&file[..]).unwrap();
+
+    let region = Loader::new(&file[..])
+        .unwrap()
+        .load_region(130, 1, 150, 3)
+        .unwrap();
+    assert_eq!(region.width, 150);
+    assert_eq!(region.height, 3);
+    assert_eq!(region.data, full.crop(130, 1, 150, 3).data);
+}
+
+#[test]
+fn region_edges_inside_spans() {
+    let file = new_rle_image(300, 2);
+    let full = radiant::load(&file[..]).unwrap();
+
+    // Windows that start and end in the middle of, or right at the edges of, literal spans
+    for &(x, width) in &[(0, 1), (1, 126), (127, 2), (128, 128), (255, 45), (299, 1)] {
+        let region = Loader::new(&file[..])
+            .unwrap()
+            .load_region(x, 0, width, 2)
+            .unwrap();
+        assert_eq!(region.data, full.crop(x, 0, width, 2).data);
+    }
+}
+
+#[test]
+fn region_old_rle() {
+    let file = old_rle_image();
+    let full = radiant::load(&file[..]).unwrap();
+
+    let region = Loader::new(&file[..])
+        .unwrap()
+        .load_region(1, 1, 2, 2)
+        .unwrap();
+    assert_eq!(region.data, full.crop(1, 1, 2, 2).data);
+}
+
+#[test]
+fn region_stops_early() {
+    let mut file = new_rle_image(16, 3);
+    // Corrupt the last scanline, which the region does not need
+    let len = file.len();
+    file.truncate(len - 20);
+
+    let region = Loader::new(&file[..])
+        .unwrap()
+        .load_region(0, 0, 16, 2)
+        .unwrap();
+    assert_eq!(region.height, 2);
+}
+
+#[test]
+fn region_out_of_bounds() {
+    let file = new_rle_image(16, 3);
+    let error = Loader::new(&file[..])
+        .unwrap()
+        .load_region(10, 0, 7, 1)
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,55 @@
+use radiant::io::ErrorKind;
+use radiant::{Loader, Rgb};
+
+/// Build an uncompressed image where the red channel of each pixel holds `x + width * y`.
+fn flat_image(width: usize, height: usize) -> Vec<u8> {
+    let mut file = format!("#?RADIANCE\n\n-Y {} +X {}\n", height, width).into_bytes();
+    for i in 0..width * height {
+        file.extend_from_slice(&[i as u8, 0, 0, 136]);
+    }
+    file
+}
+
+/// Check the red channel against the expected values, undoing the scale of the exponent.
+fn assert_red(pixels: &[Rgb], expected: &[f32]) {
+    assert_eq!(pixels.len(), expected.len());
+    for (pixel, expected) in pixels.iter().zip(expected) {
+        assert!((pixel.r * 255. / 256. - expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn scaled_averages_blocks() {
+    // 0 1 2 3
+    // 4 5 6 7
+    let file = flat_image(4, 2);
+    let image = Loader::new(&file[..]).unwrap().load_scaled(2).unwrap();
+    assert_eq!((image.width, image.height), (2, 1));
+    assert_red(&image.data, &[2.5, 4.5]);
+}
+
+#[test]
+fn scaled_partial_blocks() {
+    // 0 1 2
+    // 3 4 5
+    // 6 7 8
+    let file = flat_image(3, 3);
+    let image = Loader::new(&file[..]).unwrap().load_scaled(2).unwrap();
+    assert_eq!((image.width, image.height), (2, 2));
+    assert_red(&image.data, &[2., 3.5, 6.5, 8.]);
+}
+
+#[test]
+fn scaled_by_one_is_identity() {
+    let file = flat_image(5, 3);
+    let full = radiant::load(&file[..]).unwrap();
+    let image = Loader::new(&file[..]).unwrap().load_scaled(1).unwrap();
+    assert_eq!(image.data, full.data);
+}
+
+#[test]
+fn scaled_by_zero() {
+    let file = flat_image(4, 4);
+    let error = Loader::new(&file[..]).unwrap().load_scaled(0).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
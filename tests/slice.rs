@@ -0,0 +1,59 @@
+use radiant::io::ErrorKind;
+
+/// Build an image using the new run-length encoding, with both runs and literal spans.
+fn new_rle_image(width: usize, height: usize) -> Vec<u8> {
+    let mut file = format!("#?RADIANCE\n\n-Y {} +X {}\n", height, width).into_bytes();
+    for y in 0..height {
+        file.extend_from_slice(&[2, 2, (width >> 8) as u8, width as u8]);
+        for chunk in (0..width).collect::<Vec<_>>().chunks(128) {
+            file.push(chunk.len() as u8);
+            file.extend(chunk.iter().map(|&x| (x * 7 + y) as u8));
+        }
+        for &value in &[y as u8, 3, 130] {
+            let mut left = width;
+            while left > 0 {
+                let count = left.min(127);
+                file.extend_from_slice(&[128 | count as u8, value]);
+                left -= count;
+            }
+        }
+    }
+    file
+}
+
+#[test]
+fn slice_matches_reader() {
+    let file = new_rle_image(300, 4);
+    let expected = radiant::load(&file[..]).unwrap();
+    let image = radiant::load_from_slice(&file).unwrap();
+    assert_eq!((image.width, image.height), (300, 4));
+    assert_eq!(image.data, expected.data);
+}
+
+#[test]
+fn slice_old_rle() {
+    let file = b"#?RADIANCE\0\n\n-Y 2 +X 2\n\
+                 \xff\x00\xff\x80\x01\x01\x01\x01\
+                 \x00\xff\x00\x80\x01\x01\x01\x01";
+    let expected = radiant::load(&file[..]).unwrap();
+    let image = radiant::load_from_slice(file).unwrap();
+    assert_eq!(image.data, expected.data);
+}
+
+#[test]
+fn slice_truncated() {
+    let file = new_rle_image(64, 2);
+    let error = radiant::load_from_slice(&file[..file.len() - 1]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[cfg(feature = "memmap")]
+#[test]
+fn memmap() {
+    let file = new_rle_image(40, 3);
+    let path = std::env::temp_dir().join(format!("radiant-memmap-{}.hdr", std::process::id()));
+    std::fs::write(&path, &file).unwrap();
+    let image = radiant::load_mmap(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(image.unwrap().data, radiant::load(&file[..]).unwrap().data);
+}
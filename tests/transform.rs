@@ -0,0 +1,112 @@
+use radiant::{Image, Rgb};
+
+fn gray(v: f32) -> Rgb {
+    Rgb { r: v, g: v, b: v }
+}
+
+fn ramp(width: usize, height: usize) -> Image {
+    Image {
+        width,
+        height,
+        data: (0..width * height).map(|i| gray(i as f32)).collect(),
+    }
+}
+
+fn values(image: &Image) -> Vec<f32> {
+    image.data.iter().map(|px| px.r).collect()
+}
+
+#[test]
+fn crop() {
+    let image = ramp(4, 3).crop(1, 1, 2, 2);
+    assert_eq!(image.width, 2);
+    assert_eq!(image.height, 2);
+    assert_eq!(values(&image), [5., 6., 9., 10.]);
+}
+
+#[test]
+#[should_panic]
+fn crop_out_of_bounds() {
+    ramp(4, 3).crop(3, 0, 2, 1);
+}
+
+#[test]
+fn flips() {
+    let mut image = ramp(3, 2);
+    image.flip_horizontal();
+    assert_eq!(values(&image), [2., 1., 0., 5., 4., 3.]);
+
+    let mut image = ramp(2, 3);
+    image.flip_vertical();
+    assert_eq!(values(&image), [4., 5., 2., 3., 0., 1.]);
+}
+
+#[test]
+fn rotations() {
+    // 0 1 2
+    // 3 4 5
+    let image = ramp(3, 2);
+
+    let clockwise = image.rotate_90();
+    assert_eq!((clockwise.width, clockwise.height), (2, 3));
+    assert_eq!(values(&clockwise), [3., 0., 4., 1., 5., 2.]);
+
+    let counter_clockwise = image.rotate_270();
+    assert_eq!((counter_clockwise.width, counter_clockwise.height), (2, 3));
+    assert_eq!(values(&counter_clockwise), [2., 5., 1., 4., 0., 3.]);
+
+    let mut half_turn = image.clone();
+    half_turn.rotate_180();
+    assert_eq!(values(&half_turn), values(&clockwise.rotate_90()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn rotate_yaw() {
+    use std::f32::consts::TAU;
+
+    let image = ramp(4, 1);
+    assert_eq!(values(&image.rotate_yaw(TAU / 4.)), [3., 0., 1., 2.]);
+    assert_eq!(values(&image.rotate_yaw(-TAU / 4.)), [1., 2., 3., 0.]);
+    assert_eq!(values(&image.rotate_yaw(TAU)), values(&image));
+    assert_eq!(values(&image.rotate_yaw(TAU / 8.)), [1.5, 0.5, 1.5, 2.5]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn resample_square_pixels() {
+    // Tall pixels stretch the image vertically
+    let tall = ramp(2, 2).resample_square_pixels(2.);
+    assert_eq!((tall.width, tall.height), (2, 4));
+    assert_eq!(values(&tall), [0., 1., 0.5, 1.5, 1.5, 2.5, 2., 3.]);
+
+    // Wide pixels stretch it horizontally
+    let wide = ramp(2, 1).resample_square_pixels(0.5);
+    assert_eq!((wide.width, wide.height), (4, 1));
+    assert_eq!(values(&wide), [0., 0.25, 0.75, 1.]);
+
+    assert_eq!(
+        values(&ramp(3, 2).resample_square_pixels(1.)),
+        values(&ramp(3, 2))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn resample_square_pixels_extreme() {
+    // The stretch is limited to a factor of 16, however extreme the ratio is
+    let file = b"#?RADIANCE\nPIXASPECT=1e9\n\n-Y 1 +X 2\n\x80\x80\x80\x80\x40\x40\x40\x80";
+    let loader = radiant::Loader::new(&file[..]).unwrap();
+    let pixel_aspect = loader.header.pixel_aspect();
+    assert_eq!(pixel_aspect, 1e9);
+    let tall = loader
+        .load_image()
+        .unwrap()
+        .resample_square_pixels(pixel_aspect);
+    assert_eq!((tall.width, tall.height), (2, 16));
+
+    let wide = ramp(2, 1).resample_square_pixels(1e-9);
+    assert_eq!((wide.width, wide.height), (32, 1));
+    let wide = ramp(2, 1).resample_square_pixels(f32::MIN_POSITIVE);
+    assert_eq!((wide.width, wide.height), (32, 1));
+}
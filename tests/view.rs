@@ -0,0 +1,100 @@
+#![cfg(feature = "std")]
+
+use radiant::view::{View, ViewType};
+use radiant::Loader;
+
+fn assert_direction(actual: [f32; 3], expected: [f32; 3]) {
+    let close = actual
+        .iter()
+        .zip(&expected)
+        .all(|(a, e)| (a - e).abs() < 1e-5);
+    assert!(close, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn view_parse() {
+    let view: View =
+        "rpict -vta -vp 1 2 3 -vd 0 -1 0 -vu 0 0 1 -vh 180 -vv 180 -vo 0.5 -vs 0.1 -x 512"
+            .parse()
+            .unwrap();
+    assert_eq!(view.view_type, ViewType::Angular);
+    assert_eq!(view.position, [1., 2., 3.]);
+    assert_eq!(view.direction, [0., -1., 0.]);
+    assert_eq!((view.horizontal, view.vertical), (180., 180.));
+    assert_eq!((view.fore_clip, view.shift, view.lift), (0.5, 0.1, 0.));
+
+    assert!("-vtx".parse::<View>().is_err());
+    assert!("-vp 1 2".parse::<View>().is_err());
+    assert_eq!("".parse::<View>().unwrap(), View::default());
+}
+
+#[test]
+fn view_rays() {
+    // A 90 degree perspective view reaches 45 degrees to either side
+    let view: View = "-vtv -vd 0 1 0 -vh 90 -vv 90".parse().unwrap();
+    let s = 0.5_f32.sqrt();
+    assert_direction(view.ray(1., 0.5).unwrap().direction, [s, s, 0.]);
+    assert_direction(view.ray(0.5, 0.).unwrap().direction, [0., s, s]);
+
+    // A 180 degree angular fisheye reaches 90 degrees
+    let view = View {
+        view_type: ViewType::Angular,
+        horizontal: 180.,
+        vertical: 180.,
+        ..view
+    };
+    assert_direction(view.ray(0., 0.5).unwrap().direction, [-1., 0., 0.]);
+
+    // A 360 degree one sees everything in its inscribed circle, and nothing in the corners
+    let all_around = View {
+        horizontal: 360.,
+        vertical: 360.,
+        ..view
+    };
+    assert_direction(all_around.ray(0.25, 0.5).unwrap().direction, [-1., 0., 0.]);
+    assert!(all_around.ray(0., 0.).is_none());
+
+    // The hemispherical and stereographic fisheyes agree at the center and edges
+    for view_type in [ViewType::Hemispherical, ViewType::Fisheye] {
+        let view = View { view_type, ..view };
+        assert_direction(view.ray(0.5, 0.5).unwrap().direction, [0., 1., 0.]);
+        assert_direction(view.ray(0.5, 0.).unwrap().direction, [0., 0., 1.]);
+    }
+
+    // A 360 degree cylindrical panorama looks backwards at its edges
+    let view = View {
+        view_type: ViewType::Cylindrical,
+        horizontal: 360.,
+        ..view
+    };
+    assert_direction(view.ray(0., 0.5).unwrap().direction, [0., -1., 0.]);
+}
+
+#[test]
+fn view_parallel_and_pixels() {
+    let view: View = "-vtl -vp 0 0 0 -vd 0 0 -1 -vu 0 1 0 -vh 4 -vv 2"
+        .parse()
+        .unwrap();
+
+    // Pixel (0, 0) of a 4x2 image is half a pixel in from the top left corner
+    let ray = view.pixel_ray(0, 0, 4, 2).unwrap();
+    assert_direction(ray.origin, [-1.5, 0.5, 0.]);
+    assert_direction(ray.direction, [0., 0., -1.]);
+
+    let degenerate: View = "-vd 0 0 1".parse().unwrap();
+    assert!(degenerate.ray(0.5, 0.5).is_none());
+}
+
+#[test]
+fn view_from_header() {
+    let file = b"#?RADIANCE\n\
+        VIEW= -vtv -vp 0 0 1.5 -vd 1 0 0 -vh 60\n\
+        VIEW= -vv 40\n\
+        \n-Y 1 +X 1\n\x80\x80\x80\x80";
+    let view = Loader::new(&file[..]).unwrap().header.view().unwrap();
+    assert_eq!(view.position, [0., 0., 1.5]);
+    assert_eq!((view.horizontal, view.vertical), (60., 40.));
+
+    let file = b"#?RADIANCE\n\n-Y 1 +X 1\n\x80\x80\x80\x80";
+    assert_eq!(Loader::new(&file[..]).unwrap().header.view(), None);
+}